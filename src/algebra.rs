@@ -1,8 +1,24 @@
 //! Algebra.
 
+pub use self::convolution::*;
+pub use self::dynamic_mod_int::*;
+pub use self::factorials::*;
+pub use self::fps::*;
+pub use self::gaussian_elimination::*;
+pub use self::matrix::*;
 pub use self::mod_int::*;
+pub use self::montgomery_mod_int::*;
+pub use self::polynomial::*;
 pub use self::pow::*;
 
+mod convolution;
+mod dynamic_mod_int;
+mod factorials;
+mod fps;
+mod gaussian_elimination;
+mod matrix;
 mod mod_int;
+mod montgomery_mod_int;
+mod polynomial;
 mod pow;
 pub mod structures;
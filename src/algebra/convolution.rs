@@ -0,0 +1,494 @@
+use crate::algebra::structures::Product;
+use crate::algebra::{pow, ModInt};
+use crate::constant;
+use crate::constant::Constant;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Returns the smallest primitive root modulo `Mod`.
+///
+/// `Mod` must be a prime. The result is cached for each modulus (in each thread).
+///
+/// # Time complexity
+/// $O(\sqrt{m})$ for the first call for each $m$ = `Mod`, and amortized $O(1)$ for the others
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::primitive_root;
+/// # use k7lib::constant;
+/// constant! {
+///   const MOD: u32 = 998244353;
+/// }
+///
+/// assert_eq!(primitive_root::<MOD>().repr(), 3);
+/// ```
+pub fn primitive_root<Mod: Constant<u32>>() -> ModInt<u32, Mod> {
+  thread_local! {
+    static CACHE: RefCell<HashMap<u32, u32>> = RefCell::new(HashMap::new());
+  }
+  let m = Mod::get();
+  let g =
+    CACHE.with(|cache| *cache.borrow_mut().entry(m).or_insert_with(|| find_primitive_root(m)));
+  ModInt::new(g)
+}
+
+fn find_primitive_root(m: u32) -> u32 {
+  if m == 2 {
+    return 1;
+  }
+
+  // the prime factors of `m - 1`
+  let mut factors = vec![];
+  let mut rest = m - 1;
+  let mut p = 2;
+  while p <= rest / p {
+    if rest % p == 0 {
+      factors.push(p);
+      while rest % p == 0 {
+        rest /= p;
+      }
+    }
+    p += 1;
+  }
+  if rest > 1 {
+    factors.push(rest);
+  }
+
+  // `g` is a primitive root iff g^((m - 1) / p) != 1 for every prime factor `p` of `m - 1`.
+  let pow_mod = |x: u32, mut k: u32| {
+    let (mut x, mut y, m) = (u64::from(x), 1, u64::from(m));
+    while k > 0 {
+      if k % 2 == 1 {
+        y = y * x % m;
+      }
+      x = x * x % m;
+      k /= 2;
+    }
+    y
+  };
+  (2..).find(|&g| factors.iter().all(|&p| pow_mod(g, (m - 1) / p) != 1)).unwrap()
+}
+
+// A primitive `n`-th root of unity modulo `Mod`, or its inverse.
+fn root_of_unity<Mod: Constant<u32>>(n: usize, inverse: bool) -> ModInt<u32, Mod> {
+  let m = Mod::get();
+  assert!(n.is_power_of_two(), "length must be a power of two");
+  assert!((m - 1) as usize % n == 0, "length must divide the modulus minus 1");
+  let g = primitive_root::<Mod>();
+  let g = if inverse { g.recip().unwrap() } else { g };
+  pow(Product(g), (m - 1) / n as u32).0
+}
+
+/// Performs the number-theoretic transform in place, that is, replaces `a` with
+/// $(A(\omega^0), A(\omega^1), \ldots, A(\omega^{n - 1}))$ in bit-reversed order, where
+/// $A(x) = \sum_i a_i x^i$ and $\omega$ is the primitive $n$-th root of unity
+/// $g^{(m - 1) / n}$ for the smallest primitive root $g$ modulo $m$ = `Mod`.
+///
+/// `Mod` must be a prime.
+///
+/// # Panics
+/// Panics if $n$ = `a.len()` is not a power of two or does not divide $m - 1$.
+///
+/// # Time complexity
+/// $O(n \log(n))$, plus [`primitive_root`] on the first call for each $m$
+pub fn butterfly<Mod: Constant<u32>>(a: &mut [ModInt<u32, Mod>]) {
+  let n = a.len();
+  let root = root_of_unity::<Mod>(n, false);
+  // the decimation-in-frequency (Gentleman–Sande) butterflies
+  let mut w = root;
+  let mut h = n / 2;
+  while h >= 1 {
+    for block in a.chunks_mut(2 * h) {
+      let mut z = ModInt::new(1);
+      for i in 0..h {
+        let (x, y) = (block[i], block[i + h]);
+        block[i] = x + y;
+        block[i + h] = (x - y) * z;
+        z *= w;
+      }
+    }
+    w *= w;
+    h /= 2;
+  }
+}
+
+/// Performs the inverse of [`butterfly`] in place, except that the result is multiplied by $n$
+/// = `a.len()`.
+///
+/// `Mod` must be a prime.
+///
+/// # Panics
+/// Panics if $n$ is not a power of two or does not divide $m - 1$ where $m$ = `Mod`.
+///
+/// # Time complexity
+/// $O(n \log(n))$, plus [`primitive_root`] on the first call for each $m$
+pub fn butterfly_inv<Mod: Constant<u32>>(a: &mut [ModInt<u32, Mod>]) {
+  let n = a.len();
+  let root = root_of_unity::<Mod>(n, true);
+  // the powers of `root` used in each level, from the last
+  let mut ws = vec![];
+  let mut w = root;
+  let mut h = n / 2;
+  while h >= 1 {
+    ws.push(w);
+    w *= w;
+    h /= 2;
+  }
+  // the decimation-in-time (Cooley–Tukey) butterflies
+  let mut h = 1;
+  while h < n {
+    let w = ws.pop().unwrap();
+    for block in a.chunks_mut(2 * h) {
+      let mut z = ModInt::new(1);
+      for i in 0..h {
+        let (x, y) = (block[i], block[i + h] * z);
+        block[i] = x + y;
+        block[i + h] = x - y;
+        z *= w;
+      }
+    }
+    h *= 2;
+  }
+}
+
+/// Returns the convolution of `a` and `b`, that is, the coefficients of the product of the
+/// polynomials whose coefficients are `a` and `b`.
+///
+/// `Mod` must be a prime such that $2^k$ divides `Mod` - 1 where $2^k$ is the smallest power of two
+/// not less than `a.len() + b.len() - 1`, such as $998244353 = 119 \cdot 2^{23} + 1$. Returns an
+/// empty vector if either `a` or `b` is empty.
+///
+/// Small inputs are multiplied naively.
+///
+/// # Panics
+/// Panics if `Mod` does not satisfy the condition above.
+///
+/// # Time complexity
+/// $O((n + m) \log(n + m))$ where $n$ = `a.len()` and $m$ = `b.len()`, plus
+/// [`primitive_root`] on the first call for each `Mod`
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::{convolution, ModInt};
+/// # use k7lib::constant;
+/// constant! {
+///   const MOD: u32 = 998244353;
+/// }
+///
+/// let a = [1, 2, 3].iter().map(|&x| ModInt::<u32, MOD>::new(x)).collect::<Vec<_>>();
+/// let b = [4, 5].iter().map(|&x| ModInt::<u32, MOD>::new(x)).collect::<Vec<_>>();
+/// let c = convolution(&a, &b).into_iter().map(ModInt::repr).collect::<Vec<_>>();
+/// assert_eq!(c, [4, 13, 22, 15]);
+/// ```
+///
+/// # References
+///
+/// * [Number-theoretic transform - Wikipedia](https://en.wikipedia.org/w/index.php?title=Discrete_Fourier_transform_(general)&oldid=957779427#Number-theoretic_transform)
+pub fn convolution<Mod: Constant<u32>>(
+  a: &[ModInt<u32, Mod>],
+  b: &[ModInt<u32, Mod>],
+) -> Vec<ModInt<u32, Mod>> {
+  if a.is_empty() || b.is_empty() {
+    return vec![];
+  }
+  let len = a.len() + b.len() - 1;
+  if a.len().min(b.len()) <= 60 {
+    return convolution_naive(a, b);
+  }
+
+  let n = len.next_power_of_two();
+  let mut a = a.to_vec();
+  let mut b = b.to_vec();
+  a.resize(n, ModInt::new(0));
+  b.resize(n, ModInt::new(0));
+  butterfly(&mut a);
+  butterfly(&mut b);
+  for (x, &y) in a.iter_mut().zip(&b) {
+    *x *= y;
+  }
+  butterfly_inv(&mut a);
+  a.truncate(len);
+  let n_inv = ModInt::new(n as u32).recip().unwrap();
+  for x in &mut a {
+    *x *= n_inv;
+  }
+  a
+}
+
+fn convolution_naive<Mod: Constant<u32>>(
+  a: &[ModInt<u32, Mod>],
+  b: &[ModInt<u32, Mod>],
+) -> Vec<ModInt<u32, Mod>> {
+  let mut c = vec![ModInt::new(0); a.len() + b.len() - 1];
+  for (i, &x) in a.iter().enumerate() {
+    for (j, &y) in b.iter().enumerate() {
+      c[i + j] += x * y;
+    }
+  }
+  c
+}
+
+// NTT-friendly primes for `convolution_any_mod` and `convolution_u64`, each of which is
+// c * 2^k + 1 with k >= 24
+constant! {
+  const M1: u32 = 754_974_721;
+  const M2: u32 = 167_772_161;
+  const M3: u32 = 469_762_049;
+}
+
+// `M1 * M2 * M3`
+fn crt_modulus() -> u128 {
+  u128::from(M1::get()) * u128::from(M2::get()) * u128::from(M3::get())
+}
+
+// Convolves `a` and `b` modulo `M1`, `M2` and `M3`, and recovers the results from them by
+// Garner's algorithm.
+fn convolution_crt(a: &[u64], b: &[u64]) -> Vec<u128> {
+  fn conv<Mod: Constant<u32>>(a: &[u64], b: &[u64]) -> Vec<ModInt<u32, Mod>> {
+    let a = a.iter().map(|&x| ModInt::from(x)).collect::<Vec<_>>();
+    let b = b.iter().map(|&x| ModInt::from(x)).collect::<Vec<_>>();
+    convolution(&a, &b)
+  }
+
+  let c1 = conv::<M1>(a, b);
+  let c2 = conv::<M2>(a, b);
+  let c3 = conv::<M3>(a, b);
+  let (m1, m2) = (M1::get(), M2::get());
+  let m1_inv_mod_m2 = ModInt::<u32, M2>::new(m1).recip().unwrap();
+  let m1m2_inv_mod_m3 = ModInt::<u32, M3>::from(u64::from(m1) * u64::from(m2)).recip().unwrap();
+
+  c1.into_iter()
+    .zip(c2)
+    .zip(c3)
+    .map(|((x1, x2), x3)| {
+      // x = x1 + m1 * t1 + m1 * m2 * t2 where 0 <= t1 < m2 and 0 <= t2 < m3
+      let t1 = (x2 - ModInt::new(x1.repr())) * m1_inv_mod_m2;
+      let y = u64::from(x1.repr()) + u64::from(m1) * u64::from(t1.repr());
+      let t2 = (x3 - ModInt::from(y)) * m1m2_inv_mod_m3;
+      u128::from(y) + u128::from(m1) * u128::from(m2) * u128::from(t2.repr())
+    })
+    .collect()
+}
+
+/// Returns the convolution of `a` and `b` for an arbitrary modulus `Mod`, that is, the
+/// coefficients of the product of the polynomials whose coefficients are `a` and `b`.
+///
+/// This computes the convolutions modulo three NTT-friendly primes by [`convolution`] and
+/// combines them by the Chinese remainder theorem (Garner's algorithm). Returns an empty vector
+/// if either `a` or `b` is empty.
+///
+/// # Panics
+/// Panics if `a.len() + b.len() - 1` is greater than $2^{24}$, or $\min(n, m) (p - 1)^2$ is
+/// greater than the product of the three primes $\approx 5.9 \cdot 10^{25}$, where $p$ =
+/// `Mod`. (For example, the latter holds for $p < 2^{31}$ and $\min(n, m) \le 2^{23}$.)
+///
+/// # Time complexity
+/// $O((n + m) \log(n + m))$ where $n$ = `a.len()` and $m$ = `b.len()`
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::{convolution_any_mod, ModInt};
+/// # use k7lib::constant;
+/// constant! {
+///   const MOD: u32 = 1_000_000_007;
+/// }
+///
+/// let a = vec![ModInt::<u32, MOD>::new(1_000_000_000); 100];
+/// let c = convolution_any_mod(&a, &a);
+/// assert_eq!(c.len(), 199);
+/// assert_eq!(c[99].repr(), (100 * 49u64 % 1_000_000_007) as u32); // 10^18 = 49
+/// ```
+///
+/// # References
+///
+/// * [Garner, H. L. (1959). The residue number system. IRE Transactions on Electronic Computers, EC-8(2), 140–147.][1]
+///
+/// [1]: https://doi.org/10.1109/TEC.1959.5219515
+pub fn convolution_any_mod<Mod: Constant<u32>>(
+  a: &[ModInt<u32, Mod>],
+  b: &[ModInt<u32, Mod>],
+) -> Vec<ModInt<u32, Mod>> {
+  let m = u128::from(Mod::get() - 1);
+  let len = a.len().min(b.len()) as u128;
+  assert!(len * m * m < crt_modulus(), "sequences are too long for the modulus");
+  let a = a.iter().map(|x| u64::from(x.repr())).collect::<Vec<_>>();
+  let b = b.iter().map(|x| u64::from(x.repr())).collect::<Vec<_>>();
+  convolution_crt(&a, &b).into_iter().map(ModInt::from).collect()
+}
+
+/// Returns the convolution of `a` and `b` as integers.
+///
+/// This computes the convolutions modulo three NTT-friendly primes by [`convolution`] and
+/// combines them by the Chinese remainder theorem (Garner's algorithm). Returns an empty vector
+/// if either `a` or `b` is empty.
+///
+/// # Panics
+/// Panics if `a.len() + b.len() - 1` is greater than $2^{24}$, or $\min(n, m) \max(a) \max(b)$
+/// is not less than the product of the primes
+/// $754974721 \cdot 167772161 \cdot 469762049 \approx 5.9 \cdot 10^{25}$, which bounds the results.
+///
+/// # Time complexity
+/// $O((n + m) \log(n + m))$ where $n$ = `a.len()` and $m$ = `b.len()`
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::convolution_u64;
+/// let a = vec![1 << 30; 100];
+/// let c = convolution_u64(&a, &a);
+/// assert_eq!(c[99], 100 << 60);
+/// ```
+pub fn convolution_u64(a: &[u64], b: &[u64]) -> Vec<u128> {
+  let max = |xs: &[u64]| u128::from(xs.iter().copied().max().unwrap_or(0));
+  let len = a.len().min(b.len()) as u128;
+  let bound = (max(a) * max(b)).checked_mul(len);
+  assert!(bound.map_or(false, |x| x < crt_modulus()), "results may be too large");
+  convolution_crt(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::constant;
+  use quickcheck_macros::quickcheck;
+  use std::mem;
+
+  constant! {
+    const MOD: u32 = 998244353;
+  }
+
+  fn to_mod_ints(xs: &[u32]) -> Vec<ModInt<u32, MOD>> {
+    xs.iter().map(|&x| ModInt::new(x)).collect()
+  }
+
+  #[test]
+  fn primitive_roots() {
+    constant! {
+      const M1: u32 = 2;
+      const M2: u32 = 7;
+      const M3: u32 = 167772161;
+      const M4: u32 = 469762049;
+      const M5: u32 = 754974721;
+    }
+    assert_eq!(primitive_root::<M1>().repr(), 1);
+    assert_eq!(primitive_root::<M2>().repr(), 3);
+    assert_eq!(primitive_root::<M3>().repr(), 3);
+    assert_eq!(primitive_root::<M4>().repr(), 3);
+    assert_eq!(primitive_root::<M5>().repr(), 11);
+  }
+
+  #[quickcheck]
+  fn butterfly_prop(xs: Vec<u32>) {
+    let n = xs.len().next_power_of_two();
+    let mut a = to_mod_ints(&xs);
+    a.resize(n, ModInt::new(0));
+    let orig = a.clone();
+    butterfly(&mut a);
+
+    // the naive DFT, indexed by the bit-reversed order
+    let w = root_of_unity::<MOD>(n, false);
+    let bits = n.trailing_zeros();
+    for (i, &y) in a.iter().enumerate() {
+      let k =
+        if bits == 0 { 0 } else { i.reverse_bits() >> (8 * mem::size_of::<usize>() as u32 - bits) };
+      let wk = pow(Product(w), k as u32).0;
+      let expected = orig.iter().rev().fold(ModInt::new(0), |acc, &x| acc * wk + x);
+      assert_eq!(y, expected);
+    }
+
+    butterfly_inv(&mut a);
+    let n = ModInt::new(n as u32);
+    assert_eq!(a, orig.iter().map(|&x| x * n).collect::<Vec<_>>());
+  }
+
+  #[quickcheck]
+  fn prop(xs: Vec<u32>, ys: Vec<u32>, repeat: u8) {
+    // Repeats the inputs to make them large enough to be multiplied by NTT.
+    let repeat = usize::from(repeat % 4) * 30 + 1;
+    let a = to_mod_ints(&xs.repeat(repeat));
+    let b = to_mod_ints(&ys.repeat(repeat));
+    let c = convolution(&a, &b);
+    if a.is_empty() || b.is_empty() {
+      assert!(c.is_empty());
+    } else {
+      assert_eq!(c, convolution_naive(&a, &b));
+    }
+  }
+
+  #[quickcheck]
+  fn any_mod_prop(xs: Vec<u32>, ys: Vec<u32>, repeat: u8) {
+    constant! {
+      const P: u32 = 1_000_000_007;
+    }
+    let repeat = usize::from(repeat % 4) * 30 + 1;
+    let a = xs.repeat(repeat).into_iter().map(ModInt::<u32, P>::new).collect::<Vec<_>>();
+    let b = ys.repeat(repeat).into_iter().map(ModInt::<u32, P>::new).collect::<Vec<_>>();
+    let c = convolution_any_mod(&a, &b);
+    if a.is_empty() || b.is_empty() {
+      assert!(c.is_empty());
+    } else {
+      assert_eq!(c, convolution_naive(&a, &b));
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "sequences are too long for the modulus")]
+  fn any_mod_too_long() {
+    constant! {
+      const P: u32 = u32::max_value() - 4;
+    }
+    let a = vec![ModInt::<u32, P>::new(1); 1 << 22];
+    convolution_any_mod(&a, &a);
+  }
+
+  #[quickcheck]
+  fn u64_prop(xs: Vec<u64>, ys: Vec<u64>, shift: u8, repeat: u8) {
+    // Fills the upper bits, keeping the results less than 2^85.
+    let shift = shift % 20;
+    let repeat = usize::from(repeat % 4) * 30 + 1;
+    let a = xs.repeat(repeat).into_iter().map(|x| x << 20 >> shift).collect::<Vec<_>>();
+    let b = ys.repeat(repeat).into_iter().map(|x| x << 20 >> shift).collect::<Vec<_>>();
+    let c = convolution_u64(&a, &b);
+    if a.is_empty() || b.is_empty() {
+      assert!(c.is_empty());
+    } else {
+      let mut expected = vec![0; a.len() + b.len() - 1];
+      for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+          expected[i + j] += u128::from(x) * u128::from(y);
+        }
+      }
+      assert_eq!(c, expected);
+    }
+  }
+
+  #[test]
+  fn u64_bound() {
+    // the largest results allowed
+    let k = ((crt_modulus() - 1) >> 46) as u64;
+    let c = convolution_u64(&[1 << 40; 64], &[k; 64]);
+    assert_eq!(c[63], u128::from(k) << 46);
+    assert!(c[63] < crt_modulus() && c[63] + (1 << 46) >= crt_modulus());
+  }
+
+  #[test]
+  #[should_panic(expected = "results may be too large")]
+  fn u64_too_large() {
+    let k = ((crt_modulus() - 1) >> 46) as u64 + 1;
+    convolution_u64(&[1 << 40; 64], &[k; 64]);
+  }
+
+  #[test]
+  #[should_panic(expected = "length must divide the modulus minus 1")]
+  fn unsupported_modulus() {
+    constant! {
+      const M: u32 = 1_000_000_007;
+    }
+    let a = vec![ModInt::<u32, M>::new(1); 100];
+    convolution(&a, &a);
+  }
+}
@@ -0,0 +1,465 @@
+use crate::algebra::structures::{CommutativeSemiring, Field, Ring, Semiring};
+use crate::algebra::ModInt;
+use crate::constant::Constant;
+use crate::num::primitive::Int as PrimInt;
+
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::{Product, Sum};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// A storage of a modulus determined at runtime, with a precomputed constant for the Barrett
+/// reduction.
+///
+/// See the [`dynamic_modulus!`] macro for more details.
+#[derive(Debug)]
+pub struct Barrett {
+  modulus: AtomicU32,
+  // ceil(2^64 / modulus)
+  inv: AtomicU64,
+}
+
+impl Barrett {
+  /// Creates a new `Barrett` whose modulus is not yet set.
+  pub const fn new() -> Self {
+    Barrett { modulus: AtomicU32::new(0), inv: AtomicU64::new(0) }
+  }
+
+  /// Sets the modulus.
+  ///
+  /// # Panics
+  /// Panics if `modulus` is less than $2$.
+  pub fn set(&self, modulus: u32) {
+    assert!(modulus > 1, "modulo must be greater than 1");
+    self.modulus.store(modulus, Ordering::Relaxed);
+    self.inv.store(u64::max_value() / u64::from(modulus) + 1, Ordering::Relaxed);
+  }
+
+  /// Returns the modulus.
+  pub fn get(&self) -> u32 {
+    let modulus = self.modulus.load(Ordering::Relaxed);
+    debug_assert!(modulus > 1, "modulus not yet set");
+    modulus
+  }
+
+  /// Returns `a * b` modulo the modulus, where `a` and `b` are less than the modulus.
+  pub fn mul(&self, a: u32, b: u32) -> u32 {
+    let m = self.get();
+    let z = u64::from(a) * u64::from(b);
+    // `x` is equal to floor(z / m) or floor(z / m) + 1, so `x * m` <= `z + m` < 2^64.
+    let x = ((u128::from(z) * u128::from(self.inv.load(Ordering::Relaxed))) >> 64) as u64;
+    match z.overflowing_sub(x * u64::from(m)) {
+      (v, false) => v as u32,
+      (v, true) => v.wrapping_add(u64::from(m)) as u32,
+    }
+  }
+}
+
+impl Default for Barrett {
+  fn default() -> Self {
+    Barrett::new()
+  }
+}
+
+/// A trait for marker types that represents a modulus determined at runtime.
+///
+/// See the [`dynamic_modulus!`] macro for more details.
+pub trait DynamicModulus: Constant<u32> {
+  /// Returns the storage of the modulus.
+  fn barrett() -> &'static Barrett;
+}
+
+/// Creates a marker type that represents a modulus determined at runtime, for [`DynamicModInt`].
+///
+/// # Syntax
+///
+/// ```ignore
+/// dynamic_modulus! {
+///   NAME;
+/// }
+/// ```
+///
+/// # How it works
+///
+/// This macro defines an empty `enum` type that implements [`DynamicModulus`] with a `static`
+/// [`Barrett`] in a private scope. Unlike `static` of the [`constant!`] macro, it works
+/// anywhere and reading the modulus costs only an atomic load.
+///
+/// The type also implements [`Constant<u32>`](crate::constant::Constant), so it can be used with
+/// [`ModInt`] as well.
+///
+/// [`constant!`]: crate::constant!
+#[macro_export]
+macro_rules! dynamic_modulus {
+  () => {};
+  ($(#[$attr:meta])* $vis:vis $Name:ident; $($rest:tt)*) => {
+    $(#[$attr])*
+    $vis enum $Name {}
+
+    impl $crate::algebra::DynamicModulus for $Name {
+      fn barrett() -> &'static $crate::algebra::Barrett {
+        static BARRETT: $crate::algebra::Barrett = $crate::algebra::Barrett::new();
+        &BARRETT
+      }
+    }
+
+    impl $crate::constant::Constant<u32> for $Name {
+      fn get() -> u32 {
+        <$Name as $crate::algebra::DynamicModulus>::barrett().get()
+      }
+    }
+
+    dynamic_modulus! { $($rest)* }
+  };
+}
+
+/// A ring of integers modulo $n$ ($\mathbb{Z}/n\mathbb{Z}$), where $n$ is determined at
+/// runtime.
+///
+/// The modulus must be set by [`set_modulus`](Self::set_modulus) before any other operations,
+/// and must not be changed while values with the old modulus are alive.
+/// The modulus must be a prime to divide by every nonzero element.
+/// Multiplications use the Barrett reduction instead of divisions.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::DynamicModInt;
+/// # use k7lib::dynamic_modulus;
+/// dynamic_modulus! {
+///   MOD;
+/// }
+///
+/// let m = "998244353".parse().unwrap();
+/// DynamicModInt::<MOD>::set_modulus(m);
+///
+/// let x = DynamicModInt::<MOD>::new(1 << 31);
+/// assert_eq!((x * x).repr(), ((1u64 << 62) % 998244353) as u32);
+/// assert_eq!(format!("{}", -DynamicModInt::<MOD>::new(1)), "998244352");
+/// ```
+///
+/// # References
+///
+/// * [Barrett reduction - Wikipedia](https://en.wikipedia.org/w/index.php?title=Barrett_reduction&oldid=944496346)
+pub struct DynamicModInt<Mod> {
+  _phantom: PhantomData<fn() -> Mod>,
+  repr: u32,
+}
+
+impl<Mod> Clone for DynamicModInt<Mod> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<Mod> Copy for DynamicModInt<Mod> {}
+
+impl<Mod> PartialEq for DynamicModInt<Mod> {
+  fn eq(&self, other: &Self) -> bool {
+    self.repr.eq(&other.repr)
+  }
+}
+
+impl<Mod> Eq for DynamicModInt<Mod> {}
+
+impl<Mod> Default for DynamicModInt<Mod> {
+  fn default() -> Self {
+    DynamicModInt { _phantom: PhantomData, repr: 0 }
+  }
+}
+
+impl<Mod> Hash for DynamicModInt<Mod> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.repr.hash(state);
+  }
+}
+
+impl<Mod> Debug for DynamicModInt<Mod> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    Debug::fmt(&self.repr, f)
+  }
+}
+
+impl<Mod> Display for DynamicModInt<Mod> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    Display::fmt(&self.repr, f)
+  }
+}
+
+impl<T, Mod: DynamicModulus> From<T> for DynamicModInt<Mod>
+where
+  T: PrimInt + TryFrom<u32>,
+  u32: TryFrom<T>,
+{
+  fn from(repr: T) -> Self {
+    ModInt::<u32, Mod>::from(repr).into()
+  }
+}
+
+impl<Mod: DynamicModulus> From<ModInt<u32, Mod>> for DynamicModInt<Mod> {
+  fn from(x: ModInt<u32, Mod>) -> Self {
+    DynamicModInt::new_unchecked(x.repr())
+  }
+}
+
+impl<Mod: DynamicModulus> From<DynamicModInt<Mod>> for ModInt<u32, Mod> {
+  fn from(x: DynamicModInt<Mod>) -> Self {
+    ModInt::new(x.repr())
+  }
+}
+
+impl<Mod: DynamicModulus> DynamicModInt<Mod> {
+  fn new_unchecked(repr: u32) -> Self {
+    debug_assert!(repr < Mod::barrett().get());
+    DynamicModInt { _phantom: PhantomData, repr }
+  }
+
+  /// Sets the modulus.
+  ///
+  /// # Panics
+  /// Panics if `modulus` is less than $2$.
+  pub fn set_modulus(modulus: u32) {
+    Mod::barrett().set(modulus);
+  }
+
+  /// Returns the modulus.
+  pub fn modulus() -> u32 {
+    Mod::barrett().get()
+  }
+
+  /// Creates a new `DynamicModInt` with the given representative.
+  pub fn new(repr: u32) -> Self {
+    DynamicModInt::new_unchecked(repr % Self::modulus())
+  }
+
+  /// Returns the canonical representative.
+  pub fn repr(self) -> u32 {
+    self.repr
+  }
+
+  /// Returns the multiplicative inverse if it exists.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$ where $n$ is the modulus
+  pub fn recip(self) -> Option<Self> {
+    ModInt::<u32, Mod>::from(self).recip().map(Into::into)
+  }
+}
+
+impl<Mod: DynamicModulus> Add for DynamicModInt<Mod> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    let m = Self::modulus();
+    // `m` < 2^32, so this never overflows in `u64`.
+    let sum = u64::from(self.repr) + u64::from(rhs.repr);
+    let sum = if sum >= u64::from(m) { sum - u64::from(m) } else { sum };
+    DynamicModInt::new_unchecked(sum as u32)
+  }
+}
+
+impl<Mod: DynamicModulus> Sub for DynamicModInt<Mod> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self {
+    self + -rhs
+  }
+}
+
+impl<Mod: DynamicModulus> Mul for DynamicModInt<Mod> {
+  type Output = Self;
+
+  fn mul(self, rhs: Self) -> Self {
+    DynamicModInt::new_unchecked(Mod::barrett().mul(self.repr, rhs.repr))
+  }
+}
+
+impl<Mod: DynamicModulus> Div for DynamicModInt<Mod> {
+  type Output = Self;
+
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn div(self, rhs: Self) -> Self {
+    self * rhs.recip().expect("reciprocal does not exist")
+  }
+}
+
+impl<Mod: DynamicModulus> Neg for DynamicModInt<Mod> {
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    if self.repr == 0 {
+      self
+    } else {
+      DynamicModInt::new_unchecked(Self::modulus() - self.repr)
+    }
+  }
+}
+
+impl<Mod: DynamicModulus> AddAssign for DynamicModInt<Mod> {
+  fn add_assign(&mut self, rhs: Self) {
+    *self = *self + rhs;
+  }
+}
+
+impl<Mod: DynamicModulus> SubAssign for DynamicModInt<Mod> {
+  fn sub_assign(&mut self, rhs: Self) {
+    *self = *self - rhs;
+  }
+}
+
+impl<Mod: DynamicModulus> MulAssign for DynamicModInt<Mod> {
+  fn mul_assign(&mut self, rhs: Self) {
+    *self = *self * rhs;
+  }
+}
+
+impl<Mod: DynamicModulus> DivAssign for DynamicModInt<Mod> {
+  fn div_assign(&mut self, rhs: Self) {
+    *self = *self / rhs;
+  }
+}
+
+impl<Mod: DynamicModulus> Sum for DynamicModInt<Mod> {
+  fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+    let mut acc = DynamicModInt::new_unchecked(0);
+    for x in iter {
+      acc += x;
+    }
+    acc
+  }
+}
+
+impl<Mod: DynamicModulus> Product for DynamicModInt<Mod> {
+  fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+    let mut acc = DynamicModInt::new_unchecked(1);
+    for x in iter {
+      acc *= x;
+    }
+    acc
+  }
+}
+
+impl<Mod: DynamicModulus> Semiring for DynamicModInt<Mod> {
+  fn add(&self, rhs: &Self) -> Self {
+    *self + *rhs
+  }
+  fn mul(&self, rhs: &Self) -> Self {
+    *self * *rhs
+  }
+  fn zero() -> Self {
+    DynamicModInt::new_unchecked(0)
+  }
+  fn one() -> Self {
+    DynamicModInt::new_unchecked(1)
+  }
+}
+
+impl<Mod: DynamicModulus> CommutativeSemiring for DynamicModInt<Mod> {}
+
+impl<Mod: DynamicModulus> Ring for DynamicModInt<Mod> {
+  fn neg(&self) -> Self {
+    -*self
+  }
+}
+
+impl<Mod: DynamicModulus> Field for DynamicModInt<Mod> {
+  fn recip(&self) -> Option<Self> {
+    DynamicModInt::recip(*self)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use quickcheck_macros::quickcheck;
+  use std::convert::TryInto;
+
+  fn mod_repr(m: u32, x: i128) -> u32 {
+    x.rem_euclid(i128::from(m)).try_into().unwrap()
+  }
+
+  macro_rules! modulo {
+    ($m:expr) => {
+      if $m <= 1 {
+        return;
+      }
+      dynamic_modulus! {
+        MOD;
+      }
+      DynamicModInt::<MOD>::set_modulus($m);
+    };
+  }
+
+  #[quickcheck]
+  fn from_prop(m: u32, x: i64, y: u128) {
+    modulo!(m);
+    assert_eq!(DynamicModInt::<MOD>::from(x).repr(), mod_repr(m, i128::from(x)));
+    assert_eq!(DynamicModInt::<MOD>::from(y).repr(), (y % u128::from(m)) as u32);
+    assert_eq!(
+      DynamicModInt::<MOD>::from(i128::min_value()),
+      DynamicModInt::from(-(1i128 << 126)) * DynamicModInt::new(2)
+    );
+  }
+
+  #[quickcheck]
+  fn new_prop(m: u32, x: u32) {
+    modulo!(m);
+    let a = DynamicModInt::<MOD>::new(x);
+    assert_eq!(a.repr(), x % m);
+    assert_eq!(ModInt::from(a), ModInt::<u32, MOD>::new(x));
+    assert_eq!(DynamicModInt::from(ModInt::<u32, MOD>::new(x)), a);
+  }
+
+  #[quickcheck]
+  fn recip_prop(m: u32, x: u32) {
+    modulo!(m);
+    let x = DynamicModInt::<MOD>::new(x);
+    match x.recip() {
+      Some(x_recip) => assert_eq!((x * x_recip).repr(), 1),
+      None => assert!(ModInt::<u32, MOD>::from(x).recip().is_none()),
+    }
+  }
+
+  #[quickcheck]
+  fn ops_prop(m: u32, x: u32, y: u32) {
+    modulo!(m);
+    let a = DynamicModInt::<MOD>::new(x);
+    let b = DynamicModInt::<MOD>::new(y);
+    let (x, y) = (i128::from(x % m), i128::from(y % m));
+    assert_eq!((a + b).repr(), mod_repr(m, x + y));
+    assert_eq!((a - b).repr(), mod_repr(m, x - y));
+    assert_eq!((a * b).repr(), mod_repr(m, x * y));
+    assert_eq!((-a).repr(), mod_repr(m, -x));
+  }
+
+  #[quickcheck]
+  fn mul_large_prop(m: u32, x: u32, y: u32) {
+    // Moduli and values generated by quickcheck are small, so the upper bits are filled.
+    let m = m | 1 << 31;
+    modulo!(m);
+    let (x, y) = (x.wrapping_mul(0x9e37_79b9), y.wrapping_mul(0x85eb_ca6b));
+    let c = DynamicModInt::<MOD>::new(x) * DynamicModInt::new(y);
+    assert_eq!(c.repr(), mod_repr(m, i128::from(x % m) * i128::from(y % m)));
+  }
+
+  #[quickcheck]
+  fn sum_product_prop(m: u32, xs: Vec<u32>) {
+    modulo!(m);
+    let a = xs.iter().copied().map(DynamicModInt::<MOD>::new);
+    let b = xs.iter().copied().map(ModInt::<u32, MOD>::new);
+    assert_eq!(ModInt::from(a.clone().sum::<DynamicModInt<_>>()), b.clone().sum());
+    assert_eq!(ModInt::from(a.product::<DynamicModInt<_>>()), b.product());
+  }
+
+  #[test]
+  fn max_modulus() {
+    modulo!(u32::max_value());
+    let x = DynamicModInt::<MOD>::new(u32::max_value() - 1);
+    assert_eq!((x * x).repr(), 1);
+    assert_eq!((x + x).repr(), u32::max_value() - 2);
+  }
+}
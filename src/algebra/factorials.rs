@@ -0,0 +1,264 @@
+use crate::algebra::ModInt;
+use crate::constant::Constant;
+use crate::num::primitive::UnsignedInt as PrimUint;
+
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Formatter};
+
+/// A table of factorials and their inverses in $\mathbb{Z}/n\mathbb{Z}$, for computing binomial
+/// coefficients and related numbers.
+///
+/// The table grows on demand, so the arguments of the methods can be arbitrarily large as long as
+/// the factorials are invertible.
+///
+/// `Mod` must be a prime.
+///
+/// # Panics
+/// The methods panic if they need $k!$ for $k$ greater than or equal to `Mod`, whose inverse
+/// does not exist.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::Factorials;
+/// # use k7lib::constant;
+/// constant! {
+///   const MOD: u32 = 998244353;
+/// }
+///
+/// let mut f = Factorials::<u32, MOD>::new(10);
+/// assert_eq!(f.binom(5, 2).repr(), 10);
+/// assert_eq!(f.binom(2, 5).repr(), 0);
+/// assert_eq!(f.perm(5, 2).repr(), 20);
+/// assert_eq!(f.catalan(4).repr(), 14);
+/// assert_eq!(f.fact(20).repr(), (2432902008176640000u64 % 998244353) as u32);
+/// ```
+pub struct Factorials<Int, Mod> {
+  fact: Vec<ModInt<Int, Mod>>,
+  inv_fact: Vec<ModInt<Int, Mod>>,
+}
+
+// `derive` would require `Mod: Clone + Debug`, which the markers defined by `constant!` are not.
+impl<Int, Mod> Clone for Factorials<Int, Mod>
+where
+  Int: Clone,
+{
+  fn clone(&self) -> Self {
+    Factorials { fact: self.fact.clone(), inv_fact: self.inv_fact.clone() }
+  }
+}
+
+impl<Int, Mod> Debug for Factorials<Int, Mod>
+where
+  Int: Debug,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Factorials")
+      .field("fact", &self.fact)
+      .field("inv_fact", &self.inv_fact)
+      .finish()
+  }
+}
+
+impl<Int, Mod: Constant<Int>> Factorials<Int, Mod>
+where
+  Int: PrimUint + TryFrom<usize>,
+  usize: TryFrom<Int>,
+{
+  /// Creates a new `Factorials` with the factorials of $0, \ldots, n$ precomputed.
+  ///
+  /// # Time complexity
+  /// $O(n + \log(m))$ where $m$ = `Mod`
+  pub fn new(n: usize) -> Self {
+    let one = ModInt::new(Int::ONE);
+    let mut f = Factorials { fact: vec![one], inv_fact: vec![one] };
+    f.reserve(n);
+    f
+  }
+
+  // Extends the table so that it contains the factorial of `n`.
+  fn reserve(&mut self, n: usize) {
+    let len = self.fact.len();
+    if n < len {
+      return;
+    }
+    // The table does not grow beyond `Mod` unless required, since `Mod`! is not invertible.
+    let limit = usize::try_from(Mod::get()).unwrap_or(usize::max_value());
+    let new_len = (n + 1).max((2 * len).min(limit));
+    for i in len..new_len {
+      let x = self.fact[i - 1] * ModInt::from(i);
+      self.fact.push(x);
+    }
+    let last = self.fact[new_len - 1].recip().expect("factorial is not invertible");
+    self.inv_fact.resize(new_len, last);
+    for i in (len..new_len - 1).rev() {
+      // 1 / i! = (i + 1) / (i + 1)!
+      self.inv_fact[i] = self.inv_fact[i + 1] * ModInt::from(i + 1);
+    }
+  }
+
+  /// Returns $n!$.
+  ///
+  /// # Time complexity
+  /// Amortized $O(1)$
+  pub fn fact(&mut self, n: usize) -> ModInt<Int, Mod> {
+    self.reserve(n);
+    self.fact[n]
+  }
+
+  /// Returns $1 / n!$.
+  ///
+  /// # Time complexity
+  /// Amortized $O(1)$
+  pub fn inv_fact(&mut self, n: usize) -> ModInt<Int, Mod> {
+    self.reserve(n);
+    self.inv_fact[n]
+  }
+
+  /// Returns the binomial coefficient $\binom{n}{k}$, or $0$ if $k > n$.
+  ///
+  /// # Time complexity
+  /// Amortized $O(1)$
+  pub fn binom(&mut self, n: usize, k: usize) -> ModInt<Int, Mod> {
+    if k > n {
+      return ModInt::new(Int::ZERO);
+    }
+    self.fact(n) * self.inv_fact(k) * self.inv_fact(n - k)
+  }
+
+  /// Returns the number of $k$-permutations of $n$, $n! / (n - k)!$, or $0$ if $k > n$.
+  ///
+  /// # Time complexity
+  /// Amortized $O(1)$
+  pub fn perm(&mut self, n: usize, k: usize) -> ModInt<Int, Mod> {
+    if k > n {
+      return ModInt::new(Int::ZERO);
+    }
+    self.fact(n) * self.inv_fact(n - k)
+  }
+
+  /// Returns the multinomial coefficient $\binom{k_1 + \cdots + k_m}{k_1, \ldots, k_m}$.
+  ///
+  /// # Time complexity
+  /// Amortized $O(m)$ where $m$ = `ks.len()`
+  pub fn multinomial(&mut self, ks: &[usize]) -> ModInt<Int, Mod> {
+    let n = ks.iter().sum();
+    ks.iter().fold(self.fact(n), |acc, &k| acc * self.inv_fact(k))
+  }
+
+  /// Returns the $n$-th Catalan number $\frac{1}{n + 1} \binom{2n}{n}$.
+  ///
+  /// # Time complexity
+  /// Amortized $O(1)$
+  pub fn catalan(&mut self, n: usize) -> ModInt<Int, Mod> {
+    self.fact(2 * n) * self.inv_fact(n) * self.inv_fact(n + 1)
+  }
+
+  /// Returns the number of ways to put $n$ indistinguishable balls into $k$ distinguishable
+  /// boxes, that is, $\binom{n + k - 1}{k - 1}$ (or $[n = 0]$ if $k = 0$).
+  ///
+  /// # Time complexity
+  /// Amortized $O(1)$
+  pub fn stars_and_bars(&mut self, n: usize, k: usize) -> ModInt<Int, Mod> {
+    if k == 0 {
+      return ModInt::new(if n == 0 { Int::ONE } else { Int::ZERO });
+    }
+    self.binom(n + k - 1, k - 1)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::constant;
+  use quickcheck_macros::quickcheck;
+
+  constant! {
+    const MOD: u32 = 1_000_000_007;
+  }
+
+  // Pascal's triangle up to `n`
+  fn naive_binoms(n: usize) -> Vec<Vec<u64>> {
+    let m = u64::from(MOD::get());
+    let mut binoms = vec![vec![1]];
+    for i in 1..=n {
+      let prev = &binoms[i - 1];
+      let row = (0..=i)
+        .map(|k| {
+          let a = if k < i { prev[k] } else { 0 };
+          let b = if k > 0 { prev[k - 1] } else { 0 };
+          (a + b) % m
+        })
+        .collect();
+      binoms.push(row);
+    }
+    binoms
+  }
+
+  #[quickcheck]
+  fn binom_perm_prop(init: u8, queries: Vec<(u8, u8)>) {
+    let binoms = naive_binoms(255);
+    let m = u64::from(MOD::get());
+    let mut f = Factorials::<u32, MOD>::new(init.into());
+    for (n, k) in queries {
+      let (n, k) = (usize::from(n), usize::from(k));
+      let (binom, perm) = if k <= n {
+        (binoms[n][k], (n - k + 1..=n).fold(1, |acc, i| acc * i as u64 % m))
+      } else {
+        (0, 0)
+      };
+      assert_eq!(u64::from(f.binom(n, k).repr()), binom);
+      assert_eq!(u64::from(f.perm(n, k).repr()), perm);
+      assert_eq!((f.fact(n) * f.inv_fact(n)).repr(), 1);
+    }
+  }
+
+  #[quickcheck]
+  fn multinomial_prop(ks: Vec<u8>) {
+    let mut f = Factorials::<u32, MOD>::new(0);
+    let ks = ks.into_iter().map(usize::from).take(5).collect::<Vec<_>>();
+    // the product of the binomial coefficients choosing each part from the rest
+    let mut rest = ks.iter().sum();
+    let mut expected = ModInt::new(1);
+    for &k in &ks {
+      expected *= f.binom(rest, k);
+      rest -= k;
+    }
+    assert_eq!(f.multinomial(&ks), expected);
+  }
+
+  #[test]
+  fn catalan() {
+    let mut f = Factorials::<u32, MOD>::new(0);
+    let catalans = [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862, 16796];
+    for (n, &c) in catalans.iter().enumerate() {
+      assert_eq!(f.catalan(n).repr(), c);
+    }
+  }
+
+  #[quickcheck]
+  fn stars_and_bars_prop(n: u8, k: u8) {
+    let (n, k) = (usize::from(n % 16), usize::from(k % 5));
+    // Counts the solutions of x_1 + ... + x_k = n by dynamic programming.
+    let mut ways = vec![0u32; n + 1];
+    ways[0] = 1;
+    for _ in 0..k {
+      for i in 1..=n {
+        ways[i] += ways[i - 1];
+      }
+    }
+    let mut f = Factorials::<u32, MOD>::new(0);
+    assert_eq!(f.stars_and_bars(n, k).repr(), ways[n]);
+  }
+
+  #[test]
+  #[should_panic(expected = "factorial is not invertible")]
+  fn not_invertible() {
+    constant! {
+      const SMALL: u32 = 7;
+    }
+    let mut f = Factorials::<u32, SMALL>::new(4);
+    assert_eq!(f.fact(6).repr(), 6);
+    f.fact(7);
+  }
+}
@@ -0,0 +1,568 @@
+use crate::algebra::structures::{CommutativeSemiring, Product, Ring, Semiring};
+use crate::algebra::{convolution, pow, ModInt};
+use crate::constant::Constant;
+use crate::num::div_ceil;
+
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A formal power series, or a polynomial, with coefficients in $\mathbb{Z}/p\mathbb{Z}$.
+///
+/// The coefficients are stored in ascending order of degree, without trailing zeros. The
+/// multiplication is performed by [`convolution`], so $p$ = `Mod` must be an NTT-friendly prime
+/// such as $998244353$. Methods that compute infinite series, such as [`inv`](Self::inv), take a
+/// precision $n$ and return the series modulo $x^n$.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::{Fps, ModInt};
+/// # use k7lib::constant;
+/// constant! {
+///   const MOD: u32 = 998244353;
+/// }
+///
+/// let coeffs = |f: &Fps<ModInt<u32, MOD>>| f.coeffs().iter().map(|x| x.repr()).collect::<Vec<_>>();
+///
+/// // 1 / (1 - x - x^2) is the generating function of the Fibonacci numbers.
+/// let f = Fps::<ModInt<u32, MOD>>::new(vec![ModInt::new(1), -ModInt::new(1), -ModInt::new(1)]);
+/// assert_eq!(coeffs(&f.inv(8)), [1, 1, 2, 3, 5, 8, 13, 21]);
+///
+/// // (1 + x)^5
+/// let g = Fps::<ModInt<u32, MOD>>::new(vec![ModInt::new(1), ModInt::new(1)]);
+/// assert_eq!(coeffs(&g.pow(5, 10)), [1, 5, 10, 10, 5, 1]);
+/// ```
+///
+/// # References
+///
+/// * [Formal power series - Wikipedia](https://en.wikipedia.org/w/index.php?title=Formal_power_series&oldid=957640838)
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Fps<T> {
+  coeffs: Vec<T>,
+}
+
+type Coeff<Mod> = ModInt<u32, Mod>;
+
+impl<Mod: Constant<u32>> Fps<Coeff<Mod>> {
+  /// Creates a new `Fps` with the given coefficients in ascending order of degree.
+  pub fn new(mut coeffs: Vec<Coeff<Mod>>) -> Self {
+    while coeffs.last().map_or(false, |&c| c == ModInt::new(0)) {
+      coeffs.pop();
+    }
+    Fps { coeffs }
+  }
+
+  /// Returns the coefficients in ascending order of degree, without trailing zeros.
+  pub fn coeffs(&self) -> &[Coeff<Mod>] {
+    &self.coeffs
+  }
+
+  /// Returns the coefficients in ascending order of degree, without trailing zeros.
+  pub fn into_coeffs(self) -> Vec<Coeff<Mod>> {
+    self.coeffs
+  }
+
+  /// Returns the coefficient of $x^i$.
+  pub fn coeff(&self, i: usize) -> Coeff<Mod> {
+    self.coeffs.get(i).copied().unwrap_or_else(|| ModInt::new(0))
+  }
+
+  /// Returns the series modulo $x^n$.
+  pub fn prefix(&self, n: usize) -> Self {
+    Fps::new(self.coeffs[..n.min(self.coeffs.len())].to_vec())
+  }
+
+  // `self * rhs` modulo x^`n`
+  fn mul_prefix(&self, rhs: &Self, n: usize) -> Self {
+    let mut coeffs = convolution(&self.prefix(n).coeffs, &rhs.prefix(n).coeffs);
+    coeffs.truncate(n);
+    Fps::new(coeffs)
+  }
+
+  // `self` multiplied by x^`k`
+  fn shift(&self, k: usize) -> Self {
+    if self.coeffs.is_empty() {
+      return self.clone();
+    }
+    let mut coeffs = vec![ModInt::new(0); k];
+    coeffs.extend_from_slice(&self.coeffs);
+    Fps { coeffs }
+  }
+
+  /// Returns the formal derivative.
+  ///
+  /// # Time complexity
+  /// $O(n)$ where $n$ = `self.coeffs().len()`
+  pub fn derivative(&self) -> Self {
+    let coeffs = self.coeffs.iter().enumerate().skip(1).map(|(i, &c)| c * ModInt::from(i));
+    Fps::new(coeffs.collect())
+  }
+
+  /// Returns the formal integral whose constant term is $0$.
+  ///
+  /// # Panics
+  /// Panics if `Mod` is less than or equal to the degree.
+  ///
+  /// # Time complexity
+  /// $O(n)$ where $n$ = `self.coeffs().len()`
+  pub fn integral(&self) -> Self {
+    let invs = inverses::<Mod>(self.coeffs.len());
+    let mut coeffs = vec![ModInt::new(0)];
+    coeffs.extend(self.coeffs.iter().zip(&invs[1..]).map(|(&c, &inv)| c * inv));
+    Fps::new(coeffs)
+  }
+
+  // The coefficients of x^(`len` - 1) `self`(1 / x) modulo x^`n`, where `len` is not less than
+  // `self.coeffs().len()`.
+  fn reversed(&self, len: usize, n: usize) -> Self {
+    let mut coeffs = self.coeffs.clone();
+    coeffs.resize(len, ModInt::new(0));
+    coeffs.reverse();
+    coeffs.truncate(n);
+    Fps::new(coeffs)
+  }
+
+  /// Returns the quotient and the remainder of the polynomial division by `divisor`.
+  ///
+  /// # Panics
+  /// Panics if `divisor` is zero.
+  ///
+  /// # Time complexity
+  /// $O(n \log(n))$ where $n$ = `self.coeffs().len()`
+  pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+    assert!(!divisor.coeffs.is_empty(), "division by zero");
+    let (n, m) = (self.coeffs.len(), divisor.coeffs.len());
+    if n < m {
+      return (Fps::new(vec![]), self.clone());
+    }
+    // rev(q) = rev(f) / rev(g) modulo x^(n - m + 1)
+    let k = n - m + 1;
+    let quot = self.reversed(n, k).mul_prefix(&divisor.reversed(m, k).inv(k), k).reversed(k, k);
+    let rem = (self.clone() - quot.clone() * divisor.clone()).prefix(m - 1);
+    (quot, rem)
+  }
+
+  /// Evaluates `self` at each of `xs` by the remainder tree on the subproduct tree of
+  /// $x - x_i$.
+  ///
+  /// # Time complexity
+  /// $O(n \log(n) + k \log(k)^2)$ where $n$ = `self.coeffs().len()` and $k$ = `xs.len()`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use k7lib::algebra::{Fps, ModInt};
+  /// # use k7lib::constant;
+  /// constant! {
+  ///   const MOD: u32 = 998244353;
+  /// }
+  ///
+  /// // x^2 + 1
+  /// let f = Fps::<ModInt<u32, MOD>>::new(vec![ModInt::new(1), ModInt::new(0), ModInt::new(1)]);
+  /// let xs = [0, 1, 2, 3].iter().map(|&x| ModInt::new(x)).collect::<Vec<_>>();
+  /// let ys = f.multipoint_eval(&xs).into_iter().map(ModInt::repr).collect::<Vec<_>>();
+  /// assert_eq!(ys, [1, 2, 5, 10]);
+  /// ```
+  ///
+  /// # References
+  ///
+  /// * [Polynomial evaluation - Wikipedia](https://en.wikipedia.org/w/index.php?title=Polynomial_evaluation&oldid=955437447#Multipoint_evaluation)
+  pub fn multipoint_eval(&self, xs: &[Coeff<Mod>]) -> Vec<Coeff<Mod>> {
+    // the subproduct tree, in which the node `k` has the children `2 * k` and `2 * k + 1`
+    fn build<Mod: Constant<u32>>(k: usize, xs: &[Coeff<Mod>], tree: &mut [Fps<Coeff<Mod>>]) {
+      if xs.len() == 1 {
+        tree[k] = Fps::new(vec![-xs[0], ModInt::new(1)]);
+        return;
+      }
+      let mid = xs.len() / 2;
+      build(2 * k, &xs[..mid], tree);
+      build(2 * k + 1, &xs[mid..], tree);
+      tree[k] = tree[2 * k].clone() * tree[2 * k + 1].clone();
+    }
+
+    fn descend<Mod: Constant<u32>>(
+      k: usize,
+      f: &Fps<Coeff<Mod>>,
+      len: usize,
+      tree: &[Fps<Coeff<Mod>>],
+      ys: &mut Vec<Coeff<Mod>>,
+    ) {
+      let (_, f) = f.div_rem(&tree[k]);
+      if len == 1 {
+        ys.push(f.coeff(0));
+        return;
+      }
+      let mid = len / 2;
+      descend(2 * k, &f, mid, tree, ys);
+      descend(2 * k + 1, &f, len - mid, tree, ys);
+    }
+
+    if xs.is_empty() {
+      return vec![];
+    }
+    let mut tree = vec![Fps::new(vec![]); 4 * xs.len()];
+    build(1, xs, &mut tree);
+    let mut ys = Vec::with_capacity(xs.len());
+    descend(1, self, xs.len(), &tree, &mut ys);
+    ys
+  }
+
+  /// Returns the multiplicative inverse modulo $x^n$.
+  ///
+  /// # Panics
+  /// Panics if the constant term is $0$.
+  ///
+  /// # Time complexity
+  /// $O(n \log(n))$
+  pub fn inv(&self, n: usize) -> Self {
+    let c = self.coeff(0).recip().expect("constant term must not be zero");
+    // Newton's method: g <- g (2 - f g)
+    let mut g = Fps::new(vec![c]);
+    let mut len = 1;
+    while len < n {
+      len *= 2;
+      let fg = self.mul_prefix(&g, len);
+      g = g.mul_prefix(&(Fps::new(vec![ModInt::new(2)]) - fg), len);
+    }
+    g.prefix(n)
+  }
+
+  /// Returns the logarithm modulo $x^n$.
+  ///
+  /// # Panics
+  /// Panics if the constant term is not $1$, or `Mod` is less than $n$.
+  ///
+  /// # Time complexity
+  /// $O(n \log(n))$
+  pub fn log(&self, n: usize) -> Self {
+    assert!(self.coeff(0) == ModInt::new(1), "constant term must be 1");
+    if n == 0 {
+      return Fps::new(vec![]);
+    }
+    // log(f)' = f' / f
+    self.derivative().mul_prefix(&self.inv(n - 1), n - 1).integral()
+  }
+
+  /// Returns the exponential modulo $x^n$.
+  ///
+  /// # Panics
+  /// Panics if the constant term is not $0$, or `Mod` is less than $n$.
+  ///
+  /// # Time complexity
+  /// $O(n \log(n))$
+  pub fn exp(&self, n: usize) -> Self {
+    assert!(self.coeff(0) == ModInt::new(0), "constant term must be 0");
+    // Newton's method: g <- g (1 - log(g) + f)
+    let mut g = Fps::new(vec![ModInt::new(1)]);
+    let mut len = 1;
+    while len < n {
+      len *= 2;
+      let h = Fps::new(vec![ModInt::new(1)]) - g.log(len) + self.prefix(len);
+      g = g.mul_prefix(&h, len);
+    }
+    g.prefix(n)
+  }
+
+  /// Returns a square root modulo $x^n$ if it exists.
+  ///
+  /// # Time complexity
+  /// $O(n \log(n) + \log(p)^2)$
+  pub fn sqrt(&self, n: usize) -> Option<Self> {
+    let d = match self.coeffs.iter().position(|&c| c != ModInt::new(0)) {
+      Some(d) => d,
+      None => return Some(Fps::new(vec![])),
+    };
+    if d >= n {
+      return Some(Fps::new(vec![]));
+    }
+    if d % 2 == 1 {
+      return None;
+    }
+
+    // the square root of `f` = `self` / x^`d`, which has the same constant term as `g`
+    let f = Fps::new(self.coeffs[d..].to_vec());
+    let n = n - d / 2;
+    let mut g = Fps::new(vec![sqrt_mod(f.coeffs[0])?]);
+    let inv2 = ModInt::new(2).recip().unwrap();
+    // Newton's method: g <- (g + f / g) / 2
+    let mut len = 1;
+    while len < n {
+      len *= 2;
+      let h = g.clone() + f.mul_prefix(&g.inv(len), len);
+      g = h * Fps::new(vec![inv2]);
+    }
+    Some(g.prefix(n).shift(d / 2))
+  }
+
+  /// Returns the `k`-th power modulo $x^n$.
+  ///
+  /// # Panics
+  /// Panics if `Mod` is less than $n$.
+  ///
+  /// # Time complexity
+  /// $O(n \log(n) + \log(k))$
+  pub fn pow(&self, k: u64, n: usize) -> Self {
+    if k == 0 {
+      return Fps::new(vec![ModInt::new(1)]).prefix(n);
+    }
+    let d = match self.coeffs.iter().position(|&c| c != ModInt::new(0)) {
+      Some(d) => d,
+      None => return Fps::new(vec![]),
+    };
+    if d > 0 && k >= div_ceil(n, d) as u64 {
+      return Fps::new(vec![]);
+    }
+
+    // `self` = c x^d g where the constant term of `g` is 1
+    let shift = d * k as usize;
+    let c = self.coeffs[d];
+    let g = Fps::new(self.coeffs[d..].to_vec()) * Fps::new(vec![c.recip().unwrap()]);
+    let log = g.log(n - shift) * Fps::new(vec![ModInt::from(k)]);
+    let h = log.exp(n - shift) * Fps::new(vec![pow(Product(c), k).0]);
+    h.shift(shift)
+  }
+}
+
+// The inverses of 0 (as 0), 1, ..., `n` modulo `Mod`.
+fn inverses<Mod: Constant<u32>>(n: usize) -> Vec<Coeff<Mod>> {
+  let m = Mod::get() as usize;
+  assert!(n < m, "modulus must be greater than the degree");
+  let mut invs = vec![ModInt::new(0); n + 1];
+  if n >= 1 {
+    invs[1] = ModInt::new(1);
+  }
+  for i in 2..=n {
+    // m = (m / i) i + m % i, so 1 / i = -(m / i) / (m % i)
+    invs[i] = -invs[m % i] * ModInt::from(m / i);
+  }
+  invs
+}
+
+// A square root modulo the prime `Mod` by the Tonelli–Shanks algorithm.
+fn sqrt_mod<Mod: Constant<u32>>(a: Coeff<Mod>) -> Option<Coeff<Mod>> {
+  let p = Mod::get();
+  if a == ModInt::new(0) || p == 2 {
+    return Some(a);
+  }
+  let one = ModInt::new(1);
+  // Euler's criterion
+  if pow(Product(a), (p - 1) / 2).0 != one {
+    return None;
+  }
+  let s = (p - 1).trailing_zeros();
+  let q = (p - 1) >> s;
+  let z = (2..).map(ModInt::new).find(|&z| pow(Product(z), (p - 1) / 2).0 != one).unwrap();
+
+  // `r`^2 = `a` `t`, where the order of `c` is 2^`m` and the order of `t` divides 2^(`m` - 1)
+  let mut m = s;
+  let mut c = pow(Product(z), q).0;
+  let mut t = pow(Product(a), q).0;
+  let mut r = pow(Product(a), (q + 1) / 2).0;
+  while t != one {
+    let mut i = 0;
+    let mut t2 = t;
+    while t2 != one {
+      t2 *= t2;
+      i += 1;
+    }
+    let b = pow(Product(c), 1u32 << (m - i - 1)).0;
+    m = i;
+    c = b * b;
+    t *= c;
+    r *= b;
+  }
+  Some(r)
+}
+
+impl<Mod: Constant<u32>> Add for Fps<Coeff<Mod>> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    let (mut long, short) =
+      if self.coeffs.len() >= rhs.coeffs.len() { (self, rhs) } else { (rhs, self) };
+    for (x, &y) in long.coeffs.iter_mut().zip(&short.coeffs) {
+      *x += y;
+    }
+    Fps::new(long.coeffs)
+  }
+}
+
+impl<Mod: Constant<u32>> Sub for Fps<Coeff<Mod>> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self {
+    self + -rhs
+  }
+}
+
+impl<Mod: Constant<u32>> Mul for Fps<Coeff<Mod>> {
+  type Output = Self;
+
+  fn mul(self, rhs: Self) -> Self {
+    Fps::new(convolution(&self.coeffs, &rhs.coeffs))
+  }
+}
+
+impl<Mod: Constant<u32>> Neg for Fps<Coeff<Mod>> {
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    Fps { coeffs: self.coeffs.into_iter().map(Neg::neg).collect() }
+  }
+}
+
+impl<Mod: Constant<u32>> AddAssign for Fps<Coeff<Mod>> {
+  fn add_assign(&mut self, rhs: Self) {
+    *self = std::mem::replace(self, Fps::new(vec![])) + rhs;
+  }
+}
+
+impl<Mod: Constant<u32>> SubAssign for Fps<Coeff<Mod>> {
+  fn sub_assign(&mut self, rhs: Self) {
+    *self = std::mem::replace(self, Fps::new(vec![])) - rhs;
+  }
+}
+
+impl<Mod: Constant<u32>> MulAssign for Fps<Coeff<Mod>> {
+  fn mul_assign(&mut self, rhs: Self) {
+    *self = std::mem::replace(self, Fps::new(vec![])) * rhs;
+  }
+}
+
+impl<Mod: Constant<u32>> Semiring for Fps<Coeff<Mod>> {
+  fn add(&self, rhs: &Self) -> Self {
+    self.clone() + rhs.clone()
+  }
+  fn mul(&self, rhs: &Self) -> Self {
+    self.clone() * rhs.clone()
+  }
+  fn zero() -> Self {
+    Fps::new(vec![])
+  }
+  fn one() -> Self {
+    Fps::new(vec![ModInt::new(1)])
+  }
+}
+
+impl<Mod: Constant<u32>> CommutativeSemiring for Fps<Coeff<Mod>> {}
+
+impl<Mod: Constant<u32>> Ring for Fps<Coeff<Mod>> {
+  fn neg(&self) -> Self {
+    -self.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::constant;
+  use quickcheck_macros::quickcheck;
+
+  constant! {
+    const MOD: u32 = 998244353;
+  }
+
+  fn fps(xs: &[u32]) -> Fps<Coeff<MOD>> {
+    Fps::new(xs.iter().map(|&x| ModInt::new(x)).collect())
+  }
+
+  fn naive_mul(f: &Fps<Coeff<MOD>>, g: &Fps<Coeff<MOD>>) -> Fps<Coeff<MOD>> {
+    let mut coeffs = vec![ModInt::new(0); f.coeffs.len() + g.coeffs.len()];
+    for (i, &x) in f.coeffs.iter().enumerate() {
+      for (j, &y) in g.coeffs.iter().enumerate() {
+        coeffs[i + j] += x * y;
+      }
+    }
+    Fps::new(coeffs)
+  }
+
+  #[quickcheck]
+  fn ops_prop(xs: Vec<u32>, ys: Vec<u32>) {
+    let (f, g) = (fps(&xs), fps(&ys));
+    assert_eq!(f.clone() * g.clone(), naive_mul(&f, &g));
+    assert_eq!(f.clone() + g.clone() - g.clone(), f);
+    assert_eq!(f.clone() - f.clone(), Fps::zero());
+    assert_eq!(f.clone() * Fps::one(), f);
+    assert!(f.coeffs.last().map_or(true, |&c| c != ModInt::new(0)));
+  }
+
+  #[quickcheck]
+  fn div_rem_prop(xs: Vec<u32>, ys: Vec<u32>) {
+    let (f, g) = (fps(&xs), fps(&ys));
+    if g.coeffs.is_empty() {
+      return;
+    }
+    let (q, r) = f.div_rem(&g);
+    assert_eq!(naive_mul(&q, &g) + r.clone(), f);
+    assert!(r.coeffs.len() < g.coeffs.len());
+  }
+
+  #[quickcheck]
+  fn multipoint_eval_prop(xs: Vec<u32>, points: Vec<u32>) {
+    let f = fps(&xs);
+    let points = points.into_iter().map(ModInt::new).collect::<Vec<_>>();
+    let eval = |x: &Coeff<MOD>| f.coeffs.iter().rev().fold(ModInt::new(0), |acc, &c| acc * *x + c);
+    let ys = points.iter().map(eval).collect::<Vec<_>>();
+    assert_eq!(f.multipoint_eval(&points), ys);
+  }
+
+  #[quickcheck]
+  fn derivative_integral_prop(xs: Vec<u32>) {
+    let f = fps(&xs);
+    assert_eq!(f.integral().derivative(), f);
+    assert_eq!(f.derivative().integral(), f.clone() - fps(&[f.coeff(0).repr()]));
+  }
+
+  #[quickcheck]
+  fn inv_prop(xs: Vec<u32>, n: usize) {
+    let f = fps(&xs);
+    let n = n % 100;
+    if f.coeff(0) == ModInt::new(0) {
+      return;
+    }
+    assert_eq!((f.clone() * f.inv(n)).prefix(n), Fps::one().prefix(n));
+  }
+
+  #[quickcheck]
+  fn log_exp_prop(xs: Vec<u32>, n: usize) {
+    let n = n % 100 + 1;
+    // `f` has the constant term 0.
+    let f = fps(&xs) * fps(&[0, 1]);
+    let exp = f.exp(n);
+    assert_eq!(exp.log(n), f.prefix(n));
+    // exp(f)' = f' exp(f)
+    assert_eq!(exp.derivative().prefix(n - 1), (f.derivative() * exp).prefix(n - 1));
+  }
+
+  #[quickcheck]
+  fn sqrt_prop(xs: Vec<u32>, n: usize) {
+    let n = n % 100;
+    let f = fps(&xs);
+    let g = (f.clone() * f.clone()).sqrt(n).unwrap();
+    assert_eq!((g.clone() * g).prefix(n), (f.clone() * f).prefix(n));
+  }
+
+  #[test]
+  fn sqrt_none() {
+    // x^1 and 3 (a quadratic nonresidue modulo 998244353)
+    assert_eq!(fps(&[0, 1]).sqrt(5), None);
+    assert_eq!(fps(&[3, 1]).sqrt(5), None);
+    assert_eq!(fps(&[0, 1]).sqrt(1), Some(Fps::zero()));
+  }
+
+  #[quickcheck]
+  fn pow_prop(xs: Vec<u32>, k: u8, n: usize) {
+    let n = n % 50;
+    let k = k % 8;
+    let f = fps(&xs);
+    let expected = (0..k).fold(Fps::one(), |acc, _| naive_mul(&acc, &f)).prefix(n);
+    assert_eq!(f.pow(u64::from(k), n), expected);
+  }
+
+  #[test]
+  fn pow_large() {
+    // (x^2)^(2^62) modulo x^100
+    assert_eq!(fps(&[0, 0, 1]).pow(1 << 62, 100), Fps::zero());
+    // (1 + x)^(p + 1) = (1 + x^p)(1 + x) = 1 + x modulo x^100
+    assert_eq!(fps(&[1, 1]).pow(u64::from(MOD::get()) + 1, 100), fps(&[1, 1]));
+  }
+}
@@ -0,0 +1,418 @@
+use crate::algebra::structures::{Field, Real};
+use crate::algebra::{
+  DynamicModInt, DynamicModulus, Matrix, ModInt, MontgomeryModInt, MontgomeryModulus,
+};
+use crate::constant::Constant;
+use crate::num::primitive::{SignedInt as PrimSint, UnsignedInt as PrimUint};
+use crate::num::Ratio;
+
+/// A trait for fields whose elements can be chosen as pivots in Gaussian elimination.
+///
+/// The default implementations are for exact arithmetic.
+pub trait Pivot: Field + PartialEq {
+  /// Returns whether `self` is regarded as zero.
+  fn is_negligible(&self) -> bool {
+    *self == Self::zero()
+  }
+
+  /// Returns whether `self` is a better pivot than `other`, where both are not negligible.
+  /// Among the best ones, the first candidate is chosen.
+  fn is_better_pivot(&self, _other: &Self) -> bool {
+    false
+  }
+}
+
+impl<Int, Mod: Constant<Int>> Pivot for ModInt<Int, Mod> where Int: PrimUint {}
+
+impl<Mod: MontgomeryModulus> Pivot for MontgomeryModInt<Mod> {}
+
+impl<Mod: DynamicModulus> Pivot for DynamicModInt<Mod> {}
+
+impl<Int: PrimSint> Pivot for Ratio<Int> {}
+
+/// Absolute values not greater than $10^{-9}$ are negligible, and the largest absolute value is
+/// chosen (partial pivoting).
+impl Pivot for Real {
+  fn is_negligible(&self) -> bool {
+    self.0.abs() <= 1e-9
+  }
+
+  fn is_better_pivot(&self, other: &Self) -> bool {
+    self.0.abs() > other.0.abs()
+  }
+}
+
+/// The solutions of a system of linear equations, namely a particular solution and a basis of
+/// the kernel of the coefficient matrix.
+///
+/// See [`Matrix::solve`] for more details.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SolutionSpace<T> {
+  /// A particular solution.
+  pub particular: Vec<T>,
+  /// A basis of the kernel of the coefficient matrix.
+  pub kernel_basis: Vec<Vec<T>>,
+}
+
+impl<F> Matrix<F>
+where
+  F: Pivot,
+{
+  // Transforms `self` into the reduced row echelon form by Gauss–Jordan elimination, only with
+  // pivots in the first `cols` columns. Returns the columns of the pivots, and the product of
+  // the determinants of the elementary row operations performed.
+  fn reduce(&mut self, cols: usize) -> (Vec<usize>, F) {
+    let mut pivots = vec![];
+    let mut det = F::one();
+    for j in 0..cols {
+      let r = pivots.len();
+      let mut candidates = (r..self.rows()).filter(|&i| !self[(i, j)].is_negligible());
+      let i = match candidates.next() {
+        Some(i) => {
+          candidates.fold(i, |i, k| if self[(k, j)].is_better_pivot(&self[(i, j)]) { k } else { i })
+        }
+        None => continue,
+      };
+      if i != r {
+        self.swap_rows(i, r);
+        det = det.neg();
+      }
+      let p = self[(r, j)].clone();
+      det = det.mul(&p);
+      let inv = p.recip().expect("nonzero pivot is not invertible (is the modulus a prime?)");
+      for x in self.row_mut(r) {
+        *x = x.mul(&inv);
+      }
+      let pivot_row = self.row(r).to_vec();
+      for i in (0..self.rows()).filter(|&i| i != r) {
+        let c = self[(i, j)].clone();
+        if c != F::zero() {
+          for (x, y) in self.row_mut(i).iter_mut().zip(&pivot_row) {
+            *x = x.add(&c.mul(y).neg());
+          }
+        }
+      }
+      pivots.push(j);
+    }
+    (pivots, det)
+  }
+
+  /// Returns the rank by Gaussian elimination.
+  ///
+  /// # Panics
+  /// Panics if a nonzero element is not invertible.
+  ///
+  /// # Time complexity
+  /// $O(nm \min(n, m))$ where $n \times m$ is the size of `self`
+  pub fn rank(&self) -> usize {
+    self.clone().reduce(self.cols()).0.len()
+  }
+
+  /// Returns the determinant by Gaussian elimination.
+  ///
+  /// See [`det_euclidean`](Matrix::det_euclidean) for [`ModInt`] with a composite modulus.
+  ///
+  /// # Panics
+  /// Panics if `self` is not square, or a nonzero element is not invertible.
+  ///
+  /// # Time complexity
+  /// $O(n^3)$ where $n \times n$ is the size of `self`
+  pub fn det(&self) -> F {
+    assert!(self.rows() == self.cols(), "matrix must be square");
+    let (pivots, det) = self.clone().reduce(self.cols());
+    if pivots.len() == self.rows() {
+      det
+    } else {
+      F::zero()
+    }
+  }
+
+  /// Returns the inverse matrix by Gauss–Jordan elimination if it exists.
+  ///
+  /// # Panics
+  /// Panics if `self` is not square, or a nonzero element is not invertible.
+  ///
+  /// # Time complexity
+  /// $O(n^3)$ where $n \times n$ is the size of `self`
+  pub fn inverse(&self) -> Option<Self> {
+    assert!(self.rows() == self.cols(), "matrix must be square");
+    let n = self.rows();
+    // [`self` | I]
+    let mut a = Matrix::new(n, 2 * n);
+    for i in 0..n {
+      a.row_mut(i)[..n].clone_from_slice(self.row(i));
+      a[(i, n + i)] = F::one();
+    }
+    if a.reduce(n).0.len() < n {
+      return None;
+    }
+    let mut inv = Matrix::new(n, n);
+    for i in 0..n {
+      inv.row_mut(i).clone_from_slice(&a.row(i)[n..]);
+    }
+    Some(inv)
+  }
+
+  /// Solves the system of linear equations $Ax = b$ where $A$ = `self` and $b$ = `b`, by
+  /// Gauss–Jordan elimination. Returns the solutions, or `None` if no solution exists.
+  ///
+  /// Every solution is represented as the sum of the particular solution and a linear
+  /// combination of the kernel basis.
+  ///
+  /// # Panics
+  /// Panics if `b.len()` differs from the number of rows of `self`, or a nonzero element is not
+  /// invertible.
+  ///
+  /// # Time complexity
+  /// $O(nm \min(n, m))$ where $n \times m$ is the size of `self`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use k7lib::algebra::{Matrix, ModInt};
+  /// # use k7lib::constant;
+  /// constant! {
+  ///   const MOD: u32 = 998244353;
+  /// }
+  ///
+  /// let m = |x: i32| ModInt::<u32, MOD>::from(x);
+  /// // x + y + z = 6, x - y = -1
+  /// let a = Matrix::from_rows(vec![vec![m(1), m(1), m(1)], vec![m(1), m(-1), m(0)]]);
+  /// let s = a.solve(&[m(6), m(-1)]).unwrap();
+  /// assert_eq!(s.particular, [m(5) / m(2), m(7) / m(2), m(0)]);
+  /// assert_eq!(s.kernel_basis, [vec![-m(1) / m(2), -m(1) / m(2), m(1)]]);
+  /// ```
+  pub fn solve(&self, b: &[F]) -> Option<SolutionSpace<F>> {
+    assert!(b.len() == self.rows(), "length of b must be the number of rows");
+    let (n, m) = (self.rows(), self.cols());
+    // [`self` | `b`]
+    let mut a = Matrix::new(n, m + 1);
+    for i in 0..n {
+      a.row_mut(i)[..m].clone_from_slice(self.row(i));
+      a[(i, m)] = b[i].clone();
+    }
+    let (pivots, _) = a.reduce(m);
+    if (pivots.len()..n).any(|i| !a[(i, m)].is_negligible()) {
+      return None;
+    }
+
+    let mut particular = vec![F::zero(); m];
+    for (i, &j) in pivots.iter().enumerate() {
+      particular[j] = a[(i, m)].clone();
+    }
+    let mut is_pivot = vec![false; m];
+    for &j in &pivots {
+      is_pivot[j] = true;
+    }
+    let kernel_basis = (0..m)
+      .filter(|&k| !is_pivot[k])
+      .map(|k| {
+        let mut v = vec![F::zero(); m];
+        v[k] = F::one();
+        for (i, &j) in pivots.iter().enumerate() {
+          v[j] = a[(i, k)].neg();
+        }
+        v
+      })
+      .collect();
+    Some(SolutionSpace { particular, kernel_basis })
+  }
+}
+
+impl<Int, Mod: Constant<Int>> Matrix<ModInt<Int, Mod>>
+where
+  Int: PrimUint,
+{
+  /// Returns the determinant by Gaussian elimination without divisions, where rows are reduced
+  /// like the Euclidean algorithm. `Mod` need not be a prime.
+  ///
+  /// # Panics
+  /// Panics if `self` is not square.
+  ///
+  /// # Time complexity
+  /// $O(n^3 \log(p))$ where $n \times n$ is the size of `self` and $p$ = `Mod`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use k7lib::algebra::{Matrix, ModInt};
+  /// # use k7lib::constant;
+  /// constant! {
+  ///   const MOD: u32 = 12;
+  /// }
+  ///
+  /// let m = |x: u32| ModInt::<u32, MOD>::new(x);
+  /// let a = Matrix::from_rows(vec![vec![m(2), m(3)], vec![m(4), m(9)]]);
+  /// assert_eq!(a.det_euclidean(), m(6)); // 2 * 9 - 3 * 4
+  /// ```
+  pub fn det_euclidean(&self) -> ModInt<Int, Mod> {
+    assert!(self.rows() == self.cols(), "matrix must be square");
+    let n = self.rows();
+    let zero = ModInt::new(Int::ZERO);
+    let mut a = self.clone();
+    let mut det = ModInt::new(Int::ONE);
+    for j in 0..n {
+      for i in j + 1..n {
+        // Reduces `a[(j, j)]` and `a[(i, j)]` to their gcd and zero, like the Euclidean
+        // algorithm on their representatives.
+        while a[(i, j)] != zero {
+          let q = ModInt::new(a[(j, j)].repr() / a[(i, j)].repr());
+          let row_i = a.row(i).to_vec();
+          for (x, &y) in a.row_mut(j).iter_mut().zip(&row_i) {
+            *x -= q * y;
+          }
+          a.swap_rows(i, j);
+          det = -det;
+        }
+      }
+      det *= a[(j, j)];
+    }
+    det
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::constant;
+  use quickcheck_macros::quickcheck;
+
+  constant! {
+    const MOD: u32 = 7;
+  }
+
+  type M = ModInt<u32, MOD>;
+
+  // a matrix of size `rows` x `cols` with the elements taken from `xs` cyclically
+  fn matrix<Mod: Constant<u32>>(xs: &[u32], rows: usize, cols: usize) -> Matrix<ModInt<u32, Mod>> {
+    let mut elems = xs.iter().copied().chain(Some(1)).cycle().map(ModInt::new);
+    Matrix::from_rows((0..rows).map(|_| elems.by_ref().take(cols).collect()).collect())
+  }
+
+  // the determinant by the Leibniz formula
+  fn naive_det<Mod: Constant<u32>>(a: &Matrix<ModInt<u32, Mod>>) -> ModInt<u32, Mod> {
+    fn go<Mod: Constant<u32>>(
+      a: &Matrix<ModInt<u32, Mod>>,
+      i: usize,
+      used: &mut Vec<bool>,
+    ) -> ModInt<u32, Mod> {
+      if i == used.len() {
+        return ModInt::new(1);
+      }
+      let mut sum = ModInt::new(0);
+      // the sign is given by the number of the unused columns before `j`
+      let mut sign = ModInt::new(1);
+      for j in 0..used.len() {
+        if !used[j] {
+          used[j] = true;
+          sum += sign * a[(i, j)] * go(a, i + 1, used);
+          used[j] = false;
+          sign = -sign;
+        }
+      }
+      sum
+    }
+    go(a, 0, &mut vec![false; a.rows()])
+  }
+
+  #[quickcheck]
+  fn det_prop(xs: Vec<u32>, n: usize) {
+    let n = n % 6;
+    let a = matrix::<MOD>(&xs, n, n);
+    let det = naive_det(&a);
+    assert_eq!(a.det(), det);
+    assert_eq!(a.det_euclidean(), det);
+    assert_eq!(a.rank() == n, det != M::new(0));
+  }
+
+  #[quickcheck]
+  fn det_euclidean_prop(xs: Vec<u32>, n: usize) {
+    constant! {
+      const COMPOSITE: u32 = 36;
+    }
+    let n = n % 6;
+    let a = matrix::<COMPOSITE>(&xs, n, n);
+    assert_eq!(a.det_euclidean(), naive_det(&a));
+  }
+
+  #[test]
+  #[should_panic(expected = "nonzero pivot is not invertible (is the modulus a prime?)")]
+  fn det_composite() {
+    constant! {
+      const COMPOSITE: u32 = 36;
+    }
+    matrix::<COMPOSITE>(&[2, 0, 0, 1], 2, 2).det();
+  }
+
+  #[quickcheck]
+  fn inverse_prop(xs: Vec<u32>, n: usize) {
+    let n = n % 6;
+    let a = matrix::<MOD>(&xs, n, n);
+    match a.inverse() {
+      Some(inv) => {
+        assert_eq!(&a * &inv, Matrix::identity(n));
+        assert_eq!(&inv * &a, Matrix::identity(n));
+      }
+      None => assert_eq!(a.det(), M::new(0)),
+    }
+  }
+
+  #[quickcheck]
+  fn solve_prop(xs: Vec<u32>, ys: Vec<u32>, rows: usize, cols: usize, coeffs: Vec<u32>) {
+    let (rows, cols) = (rows % 5 + 1, cols % 5 + 1);
+    let a = matrix::<MOD>(&xs, rows, cols);
+    let b = matrix::<MOD>(&ys, rows, 1).transpose().row(0).to_vec();
+    let mul =
+      |x: &[M]| (0..rows).map(|i| (0..cols).map(|j| a[(i, j)] * x[j]).sum()).collect::<Vec<M>>();
+
+    // Enumerates all the vectors to check the existence of solutions.
+    let exists = (0..7usize.pow(cols as u32)).any(|mut k| {
+      let x = (0..cols)
+        .map(|_| {
+          let d = k % 7;
+          k /= 7;
+          M::new(d as u32)
+        })
+        .collect::<Vec<_>>();
+      mul(&x) == b
+    });
+
+    match a.solve(&b) {
+      Some(s) => {
+        assert!(exists);
+        assert_eq!(s.kernel_basis.len(), cols - a.rank());
+        // an arbitrary solution
+        let mut x = s.particular.clone();
+        for (v, &c) in s.kernel_basis.iter().zip(coeffs.iter().chain(std::iter::repeat(&1))) {
+          assert_eq!(mul(v), vec![M::new(0); rows]);
+          for (x, &y) in x.iter_mut().zip(v) {
+            *x += M::new(c) * y;
+          }
+        }
+        assert_eq!(mul(&x), b);
+      }
+      None => assert!(!exists),
+    }
+  }
+
+  #[test]
+  fn real() {
+    let real = |rows: &[&[f64]]| {
+      Matrix::from_rows(rows.iter().map(|row| row.iter().map(|&x| Real(x)).collect()).collect())
+    };
+    // The first pivot is tiny, which is avoided by partial pivoting.
+    let a = real(&[&[1e-12, 1.0, 1.0], &[1.0, 1.0, 0.0], &[0.0, 1.0, 2.0]]);
+    let b = [Real(2.0), Real(2.0), Real(3.0)];
+    let s = a.solve(&b).unwrap();
+    assert!(s.kernel_basis.is_empty());
+    assert!(s.particular.iter().all(|x| (x.0 - 1.0).abs() < 1e-9));
+    assert!((a.det().0 - -1.0).abs() < 1e-9);
+    let inv = a.inverse().unwrap();
+    let id = &a * &inv;
+    assert!((0..3).all(|i| (0..3).all(|j| (id[(i, j)].0 - (i == j) as u8 as f64).abs() < 1e-9)));
+
+    let singular = real(&[&[1.0, 2.0], &[2.0, 4.0 + 1e-12]]);
+    assert_eq!(singular.rank(), 1);
+    assert!(singular.inverse().is_none());
+  }
+}
@@ -0,0 +1,310 @@
+use crate::algebra::pow;
+use crate::algebra::structures::{Monoid, Semigroup, Semiring};
+use crate::num::primitive::UnsignedInt as PrimUint;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::ops::{Add, Index, IndexMut, Mul};
+
+/// A matrix over a semiring.
+///
+/// For the [`Monoid`] impl, the product of two matrices is taken as the operation, and the empty
+/// $0 \times 0$ matrix is regarded as the identity matrix of every size. Use the inherent
+/// [`pow`](Matrix::pow) to compute the powers of square matrices, since
+/// [`algebra::pow`](crate::algebra::pow) returns the empty matrix as the $0$-th power. (Likewise,
+/// the inherent [`identity`](Matrix::identity) creates the identity matrix of the given size,
+/// unlike [`Monoid::identity`].)
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::Matrix;
+/// // the Fibonacci numbers
+/// let a = Matrix::from_rows(vec![vec![1u64, 1], vec![1, 0]]);
+/// assert_eq!(a.pow(40u32)[(0, 1)], 102334155);
+/// assert_eq!(a.pow(0u32)[(0, 1)], 0);
+/// ```
+///
+/// See also [`MinPlus`](crate::algebra::structures::MinPlus) for shortest paths.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Matrix<S> {
+  rows: usize,
+  cols: usize,
+  elems: Vec<S>,
+}
+
+impl<S> Matrix<S>
+where
+  S: Semiring,
+{
+  /// Creates a new zero matrix of size `rows` $\times$ `cols`.
+  ///
+  /// # Time complexity
+  /// $O(nm)$ where $n$ = `rows` and $m$ = `cols`
+  pub fn new(rows: usize, cols: usize) -> Self {
+    Matrix { rows, cols, elems: vec![S::zero(); rows * cols] }
+  }
+
+  /// Creates a new identity matrix of size `n` $\times$ `n`.
+  ///
+  /// # Time complexity
+  /// $O(n^2)$
+  pub fn identity(n: usize) -> Self {
+    let mut a = Matrix::new(n, n);
+    for i in 0..n {
+      a[(i, i)] = S::one();
+    }
+    a
+  }
+
+  /// Creates a new matrix from its rows.
+  ///
+  /// # Panics
+  /// Panics if the rows have different lengths.
+  ///
+  /// # Time complexity
+  /// $O(nm)$ where $n$ = `rows.len()` and $m$ is the length of each row
+  pub fn from_rows(rows: Vec<Vec<S>>) -> Self {
+    let cols = rows.first().map_or(0, |row| row.len());
+    assert!(rows.iter().all(|row| row.len() == cols), "rows must have the same length");
+    Matrix { rows: rows.len(), cols, elems: rows.into_iter().flatten().collect() }
+  }
+
+  /// Returns the number of rows.
+  pub fn rows(&self) -> usize {
+    self.rows
+  }
+
+  /// Returns the number of columns.
+  pub fn cols(&self) -> usize {
+    self.cols
+  }
+
+  /// Returns the `i`-th row.
+  ///
+  /// # Panics
+  /// Panics if `i` is out of bounds.
+  pub fn row(&self, i: usize) -> &[S] {
+    assert_index(i, self.rows);
+    &self.elems[i * self.cols..(i + 1) * self.cols]
+  }
+
+  /// Returns the `i`-th row.
+  ///
+  /// # Panics
+  /// Panics if `i` is out of bounds.
+  pub fn row_mut(&mut self, i: usize) -> &mut [S] {
+    assert_index(i, self.rows);
+    &mut self.elems[i * self.cols..(i + 1) * self.cols]
+  }
+
+  /// Swaps the `i`-th and `j`-th rows.
+  ///
+  /// # Panics
+  /// Panics if `i` or `j` is out of bounds.
+  ///
+  /// # Time complexity
+  /// $O(m)$ where $m$ is the number of columns
+  pub fn swap_rows(&mut self, i: usize, j: usize) {
+    assert_index(i, self.rows);
+    assert_index(j, self.rows);
+    for k in 0..self.cols {
+      self.elems.swap(i * self.cols + k, j * self.cols + k);
+    }
+  }
+
+  /// Raises the square matrix `self` to the power of `exp`, where the $0$-th power is the
+  /// identity matrix of the same size.
+  ///
+  /// # Panics
+  /// Panics if `self` is not square.
+  ///
+  /// # Time complexity
+  /// $O(n^3 \log(k))$ where $n \times n$ is the size of `self` and $k$ = `exp`
+  pub fn pow<Int>(&self, exp: Int) -> Self
+  where
+    Int: PrimUint,
+  {
+    assert!(self.rows == self.cols, "matrix must be square");
+    if exp == Int::ZERO {
+      Matrix::identity(self.rows)
+    } else {
+      pow(self.clone(), exp)
+    }
+  }
+
+  /// Returns the transposed matrix.
+  ///
+  /// # Time complexity
+  /// $O(nm)$ where $n \times m$ is the size of `self`
+  pub fn transpose(&self) -> Self {
+    let elems = (0..self.cols)
+      .flat_map(|j| (0..self.rows).map(move |i| (i, j)))
+      .map(|(i, j)| self[(i, j)].clone())
+      .collect();
+    Matrix { rows: self.cols, cols: self.rows, elems }
+  }
+}
+
+impl<S> Index<(usize, usize)> for Matrix<S> {
+  type Output = S;
+
+  fn index(&self, (i, j): (usize, usize)) -> &S {
+    assert_index(i, self.rows);
+    assert_index(j, self.cols);
+    &self.elems[i * self.cols + j]
+  }
+}
+
+impl<S> IndexMut<(usize, usize)> for Matrix<S> {
+  fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut S {
+    assert_index(i, self.rows);
+    assert_index(j, self.cols);
+    &mut self.elems[i * self.cols + j]
+  }
+}
+
+impl<S> Add for Matrix<S>
+where
+  S: Semiring,
+{
+  type Output = Self;
+
+  /// # Panics
+  /// Panics if the sizes differ.
+  fn add(mut self, rhs: Self) -> Self {
+    assert!((self.rows, self.cols) == (rhs.rows, rhs.cols), "sizes must be equal");
+    for (x, y) in self.elems.iter_mut().zip(&rhs.elems) {
+      *x = x.add(y);
+    }
+    self
+  }
+}
+
+impl<S> Mul for Matrix<S>
+where
+  S: Semiring,
+{
+  type Output = Self;
+
+  fn mul(self, rhs: Self) -> Self {
+    &self * &rhs
+  }
+}
+
+impl<S> Mul for &Matrix<S>
+where
+  S: Semiring,
+{
+  type Output = Matrix<S>;
+
+  /// # Panics
+  /// Panics if the number of columns of `self` differs from the number of rows of `rhs`.
+  ///
+  /// # Time complexity
+  /// $O(nmk)$ where $n \times m$ and $m \times k$ are the sizes of `self` and `rhs`
+  fn mul(self, rhs: Self) -> Matrix<S> {
+    assert!(self.cols == rhs.rows, "sizes must be compatible");
+    let mut c = Matrix::<S>::new(self.rows, rhs.cols);
+    for i in 0..self.rows {
+      for k in 0..self.cols {
+        let x = &self[(i, k)];
+        for (z, y) in c.row_mut(i).iter_mut().zip(rhs.row(k)) {
+          *z = z.add(&x.mul(y));
+        }
+      }
+    }
+    c
+  }
+}
+
+impl<S> Semigroup for Matrix<S>
+where
+  S: Semiring,
+{
+  fn op(&self, rhs: &Self) -> Self {
+    if self.rows == 0 && self.cols == 0 {
+      rhs.clone()
+    } else if rhs.rows == 0 && rhs.cols == 0 {
+      self.clone()
+    } else {
+      self * rhs
+    }
+  }
+}
+
+impl<S> Monoid for Matrix<S>
+where
+  S: Semiring,
+{
+  fn identity() -> Self {
+    Matrix { rows: 0, cols: 0, elems: vec![] }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::algebra::pow;
+  use quickcheck::{Arbitrary, Gen};
+  use quickcheck_macros::quickcheck;
+  use rand::Rng as _;
+
+  impl<S> Arbitrary for Matrix<S>
+  where
+    S: Semiring + Arbitrary,
+  {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      let (rows, cols) = (g.gen_range(1, 5), g.gen_range(1, 5));
+      Matrix { rows, cols, elems: (0..rows * cols).map(|_| S::arbitrary(g)).collect() }
+    }
+  }
+
+  // the square matrix of size `n` with the elements taken from `a` cyclically
+  fn square(a: &Matrix<i64>, n: usize) -> Matrix<i64> {
+    let mut elems = a.elems.iter().copied().map(|x| x % 10).cycle();
+    let rows = (0..n).map(|_| elems.by_ref().take(n).collect()).collect();
+    Matrix::from_rows(rows)
+  }
+
+  #[quickcheck]
+  fn mul_prop(a: Matrix<i64>, b: Matrix<i64>, c: Matrix<i64>) {
+    let (a, b, c) = (square(&a, 3), square(&b, 3), square(&c, 3));
+    assert_eq!(&(&a * &b) * &c, &a * &(&b * &c));
+    assert_eq!(&a * &Matrix::identity(3), a);
+    assert_eq!(&Matrix::identity(3) * &a, a);
+    assert_eq!((a.clone() + b.clone()) * c.clone(), a.clone() * c.clone() + b * c);
+    assert_eq!(a.op(&Monoid::identity()), a);
+  }
+
+  #[quickcheck]
+  fn transpose_prop(a: Matrix<i64>, b: Matrix<i64>) {
+    assert_eq!(a.transpose().transpose(), a);
+    assert_eq!(a.transpose()[(0, 0)], a[(0, 0)]);
+    let b = Matrix::from_rows(
+      (0..a.cols())
+        .map(|i| (0..b.cols()).map(|j| b.elems[(i + j) % b.elems.len()]).collect())
+        .collect(),
+    );
+    assert_eq!((&a * &b).transpose(), &b.transpose() * &a.transpose());
+  }
+
+  #[quickcheck]
+  fn pow_prop(a: Matrix<i64>, n: u8) {
+    let a = square(&a, 2).transpose();
+    let n = n % 8;
+    let expected = (0..n).fold(Matrix::identity(2), |acc, _| &acc * &a);
+    assert_eq!(a.pow(n), expected);
+    let result = pow(a, n);
+    if n == 0 {
+      assert_eq!(result, Monoid::identity());
+    } else {
+      assert_eq!(result, expected);
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "sizes must be compatible")]
+  fn mul_incompatible() {
+    let _ = &Matrix::<i64>::new(2, 3) * &Matrix::new(2, 3);
+  }
+}
@@ -1,8 +1,8 @@
-use crate::algebra::structures::{CommutativeSemiring, Ring, Semiring};
+use crate::algebra::structures::{CommutativeSemiring, Field, Ring, Semiring};
 use crate::constant::Constant;
 use crate::num::primitive::{Int as PrimInt, UnsignedInt as PrimUint};
 
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use std::iter::{Product, Sum};
@@ -12,7 +12,7 @@ use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssi
 
 /// A ring of integers modulo $n$ ($\mathbb{Z}/n\mathbb{Z}$).
 ///
-/// `Mod` must be greater than $1$.
+/// `Mod` must be greater than $1$, and must be a prime to divide by every nonzero element.
 ///
 /// # Examples
 ///
@@ -218,11 +218,28 @@ where
 {
   type Output = Self;
 
-  #[allow(clippy::suspicious_arithmetic_impl)]
+  // Rust 1.39 cannot resolve `Int::Double % Int::Double`, only `Int::Double % &Int::Double`.
+  #[allow(clippy::op_ref)]
   fn mul(self, rhs: Self) -> Self {
     if let Some(prod) = self.repr.checked_mul(rhs.repr) {
       return ModInt::new(prod);
     }
+    if mem::size_of::<Int::Double>() > mem::size_of::<Int>() {
+      // These conversions can never fail since `Int::Double` is wider than `Int`.
+      let mod_ = Int::Double::try_from(Mod::get()).ok().unwrap();
+      let prod = self.repr.mul_wide(rhs.repr) % &mod_;
+      return ModInt::new_unchecked(prod.try_into().ok().unwrap());
+    }
+    // Only for `u128`, which has no wider type.
+    self.mul_by_doubling(rhs)
+  }
+}
+
+impl<Int, Mod: Constant<Int>> ModInt<Int, Mod>
+where
+  Int: PrimUint,
+{
+  fn mul_by_doubling(self, rhs: Self) -> Self {
     let mut lhs = self;
     let mut rhs = rhs.repr;
     let mut acc = ModInt::new_unchecked(Int::ZERO);
@@ -357,6 +374,15 @@ where
   }
 }
 
+impl<Int, Mod: Constant<Int>> Field for ModInt<Int, Mod>
+where
+  Int: PrimUint,
+{
+  fn recip(&self) -> Option<Self> {
+    ModInt::recip(*self)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -472,4 +498,53 @@ mod tests {
     let a = xs.iter().copied().map(ModInt::new).product::<ModInt<_, MOD>>();
     assert_eq!(a.repr(), mod_product(m, xs));
   }
+
+  #[quickcheck]
+  fn mul_wide_prop(m: u64, x: u64, y: u64) {
+    // moduli near 2^63, for which `checked_mul` almost always fails
+    let m = m >> 1 | 1 << 63;
+    constant! {
+      static MOD: u64 = m;
+    }
+    let (a, b) = (ModInt::<_, MOD>::new(x), ModInt::new(y));
+    let expected = u128::from(x % m) * u128::from(y % m) % u128::from(m);
+    assert_eq!(u128::from((a * b).repr()), expected);
+    assert_eq!(a * b, a.mul_by_doubling(b));
+  }
+
+  #[test]
+  fn mul_u128() {
+    constant! {
+      const MOD: u128 = (1 << 127) + 1;
+    }
+    // (-2)^2 = 4
+    let a = ModInt::<u128, MOD>::new(MOD::get() - 2);
+    assert_eq!((a * a).repr(), 4);
+  }
+
+  // Run with `cargo test --release -- --ignored` to check that the widening multiplication is
+  // faster than doubling for moduli near 2^63.
+  #[test]
+  #[ignore]
+  fn mul_wide_faster_than_doubling() {
+    use std::time::Instant;
+
+    constant! {
+      const MOD: u64 = (1 << 63) + 29;
+    }
+    let xs = (0..1_000_000u64)
+      .map(|i| ModInt::<_, MOD>::new(i.wrapping_mul(0x9e37_79b9_7f4a_7c15)))
+      .collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let wide = xs.iter().fold(ModInt::new(1), |acc, &x| acc * x);
+    let wide_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let doubling = xs.iter().fold(ModInt::new(1), |acc, &x| acc.mul_by_doubling(x));
+    let doubling_elapsed = start.elapsed();
+
+    assert_eq!(wide, doubling);
+    assert!(wide_elapsed < doubling_elapsed);
+  }
 }
@@ -0,0 +1,481 @@
+use crate::algebra::structures::{CommutativeSemiring, Field, Ring, Semiring};
+use crate::algebra::ModInt;
+use crate::constant::Constant;
+use crate::num::primitive::Int as PrimInt;
+
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::{Product, Sum};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A trait for marker types that represents a modulus for [`MontgomeryModInt`], with the
+/// constants for the Montgomery reduction computed in advance.
+///
+/// See the [`montgomery_modulus!`] macro for more details.
+///
+/// [`montgomery_modulus!`]: crate::montgomery_modulus!
+pub trait MontgomeryModulus: Constant<u64> {
+  /// Returns $-m^{-1} \bmod 2^{64}$ where $m$ = `Self::get()`.
+  fn neg_inv() -> u64;
+
+  /// Returns $2^{128} \bmod m$ where $m$ = `Self::get()`.
+  fn r2() -> u64;
+}
+
+#[doc(hidden)]
+pub const fn montgomery_neg_inv(m: u64) -> u64 {
+  // Newton's method, doubling the number of correct lower bits from 3 (m * m ≡ 1 mod 8)
+  let inv = m;
+  let inv = inv.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(inv)));
+  let inv = inv.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(inv)));
+  let inv = inv.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(inv)));
+  let inv = inv.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(inv)));
+  let inv = inv.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(inv)));
+  inv.wrapping_neg()
+}
+
+#[doc(hidden)]
+pub const fn montgomery_r2(m: u64) -> u64 {
+  let m = m as u128;
+  let r = (1 << 64) % m;
+  (r * r % m) as u64
+}
+
+/// Creates a marker type that represents a modulus for [`MontgomeryModInt`].
+///
+/// # Syntax
+///
+/// ```ignore
+/// montgomery_modulus! {
+///   // Compile-time constant value
+///   const NAME: u64 = EXPR;
+///
+///   // Runtime constant value
+///   static NAME: u64 = EXPR;
+/// }
+/// ```
+///
+/// If it contains `static`'s one, it works only in functions.
+///
+/// # How it works
+///
+/// Like the [`constant!`] macro, this macro defines an empty `enum` type that implements
+/// [`Constant<u64>`](crate::constant::Constant), and also [`MontgomeryModulus`]. The constants
+/// for the Montgomery reduction are computed at compile time for `const`, and once on the
+/// declaration for `static`.
+///
+/// [`constant!`]: crate::constant!
+#[macro_export]
+macro_rules! montgomery_modulus {
+  () => {};
+  ($(#[$attr:meta])* $vis:vis const $Name:ident: u64 = $val:expr; $($rest:tt)*) => {
+    $(#[$attr])*
+    $vis enum $Name {}
+
+    impl $crate::constant::Constant<u64> for $Name {
+      fn get() -> u64 {
+        const VAL: u64 = $val;
+        VAL
+      }
+    }
+
+    impl $crate::algebra::MontgomeryModulus for $Name {
+      fn neg_inv() -> u64 {
+        const VAL: u64 = $crate::algebra::montgomery_neg_inv($val);
+        VAL
+      }
+      fn r2() -> u64 {
+        const VAL: u64 = $crate::algebra::montgomery_r2($val);
+        VAL
+      }
+    }
+
+    montgomery_modulus! { $($rest)* }
+  };
+  ($(#[$attr:meta])* $vis:vis static $Name:ident: u64 = $val:expr; $($rest:tt)*) => {
+    $(#[$attr])*
+    $vis enum $Name {}
+
+    {
+      use ::std::cell::Cell;
+      use ::std::option::Option::{self, None, Some};
+
+      ::std::thread_local! {
+        // the modulus, `neg_inv()` and `r2()`
+        static VAL: Cell<Option<(u64, u64, u64)>> = Cell::new(None);
+      }
+      let m: u64 = $val;
+      VAL.with(|val| {
+        val.set(Some((m, $crate::algebra::montgomery_neg_inv(m), $crate::algebra::montgomery_r2(m))))
+      });
+
+      impl $crate::constant::Constant<u64> for $Name {
+        fn get() -> u64 {
+          VAL.with(|val| val.get().expect("constant not yet initialized").0)
+        }
+      }
+
+      impl $crate::algebra::MontgomeryModulus for $Name {
+        fn neg_inv() -> u64 {
+          VAL.with(|val| val.get().expect("constant not yet initialized").1)
+        }
+        fn r2() -> u64 {
+          VAL.with(|val| val.get().expect("constant not yet initialized").2)
+        }
+      }
+    }
+
+    montgomery_modulus! { $($rest)* }
+  };
+}
+
+/// A ring of integers modulo $n$ ($\mathbb{Z}/n\mathbb{Z}$) in the Montgomery representation,
+/// which multiplies fast even for 64-bit moduli.
+///
+/// `Mod` must be odd, greater than $1$ and less than $2^{62}$, and is created by the
+/// [`montgomery_modulus!`](crate::montgomery_modulus!) macro.
+/// `Mod` must be a prime to divide by every nonzero element.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::{ModInt, MontgomeryModInt};
+/// # use k7lib::montgomery_modulus;
+/// montgomery_modulus! {
+///   const MOD: u64 = (1 << 61) - 1;
+/// }
+///
+/// let x = MontgomeryModInt::<MOD>::new(1 << 60);
+/// let y = x * x * x;
+///
+/// assert_eq!(y.repr(), 1 << 58);
+/// assert_eq!(ModInt::from(y), ModInt::<u64, MOD>::new(1 << 58));
+/// ```
+///
+/// # References
+///
+/// * [Montgomery modular multiplication - Wikipedia](https://en.wikipedia.org/w/index.php?title=Montgomery_modular_multiplication&oldid=962591917)
+pub struct MontgomeryModInt<Mod> {
+  _phantom: PhantomData<fn() -> Mod>,
+  // `repr()` * 2^64 mod `Mod::get()`
+  mont: u64,
+}
+
+impl<Mod> Clone for MontgomeryModInt<Mod> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<Mod> Copy for MontgomeryModInt<Mod> {}
+
+impl<Mod> PartialEq for MontgomeryModInt<Mod> {
+  fn eq(&self, other: &Self) -> bool {
+    self.mont.eq(&other.mont)
+  }
+}
+
+impl<Mod> Eq for MontgomeryModInt<Mod> {}
+
+impl<Mod> Default for MontgomeryModInt<Mod> {
+  fn default() -> Self {
+    MontgomeryModInt { _phantom: PhantomData, mont: 0 }
+  }
+}
+
+impl<Mod> Hash for MontgomeryModInt<Mod> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.mont.hash(state);
+  }
+}
+
+impl<Mod: MontgomeryModulus> Debug for MontgomeryModInt<Mod> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    Debug::fmt(&self.repr(), f)
+  }
+}
+
+impl<Mod: MontgomeryModulus> Display for MontgomeryModInt<Mod> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    Display::fmt(&self.repr(), f)
+  }
+}
+
+impl<T, Mod: MontgomeryModulus> From<T> for MontgomeryModInt<Mod>
+where
+  T: PrimInt + TryFrom<u64>,
+  u64: TryFrom<T>,
+{
+  fn from(repr: T) -> Self {
+    ModInt::<u64, Mod>::from(repr).into()
+  }
+}
+
+impl<Mod: MontgomeryModulus> From<ModInt<u64, Mod>> for MontgomeryModInt<Mod> {
+  fn from(x: ModInt<u64, Mod>) -> Self {
+    MontgomeryModInt::from_reduced(x.repr())
+  }
+}
+
+impl<Mod: MontgomeryModulus> From<MontgomeryModInt<Mod>> for ModInt<u64, Mod> {
+  fn from(x: MontgomeryModInt<Mod>) -> Self {
+    ModInt::new(x.repr())
+  }
+}
+
+impl<Mod: MontgomeryModulus> MontgomeryModInt<Mod> {
+  fn new_unchecked(mont: u64) -> Self {
+    debug_assert!(Mod::get() > 1, "modulo must be greater than 1");
+    debug_assert!(Mod::get() % 2 == 1, "modulo must be odd");
+    debug_assert!(Mod::get() < 1 << 62, "modulo must be less than 2^62");
+    debug_assert!(mont < Mod::get());
+    MontgomeryModInt { _phantom: PhantomData, mont }
+  }
+
+  // `t` * 2^-64 mod `Mod::get()`, where `t` < `Mod::get()` * 2^64
+  fn reduce(t: u128) -> u64 {
+    let m = Mod::get();
+    let q = (t as u64).wrapping_mul(Mod::neg_inv());
+    // `t + q * m` is divisible by 2^64 and less than 2 * `m` * 2^64 < 2^127.
+    let r = ((t + u128::from(q) * u128::from(m)) >> 64) as u64;
+    if r >= m {
+      r - m
+    } else {
+      r
+    }
+  }
+
+  fn from_reduced(repr: u64) -> Self {
+    MontgomeryModInt::new_unchecked(Self::reduce(u128::from(repr) * u128::from(Mod::r2())))
+  }
+
+  /// Creates a new `MontgomeryModInt` with the given representative.
+  pub fn new(repr: u64) -> Self {
+    MontgomeryModInt::from_reduced(repr % Mod::get())
+  }
+
+  /// Returns the canonical representative.
+  pub fn repr(self) -> u64 {
+    Self::reduce(u128::from(self.mont))
+  }
+
+  /// Returns the multiplicative inverse if it exists.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$ where $n$ = `Mod`
+  pub fn recip(self) -> Option<Self> {
+    ModInt::from(self).recip().map(Self::from)
+  }
+}
+
+impl<Mod: MontgomeryModulus> Add for MontgomeryModInt<Mod> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    // `Mod::get()` < 2^62, so this never overflows.
+    let mut sum = self.mont + rhs.mont;
+    if sum >= Mod::get() {
+      sum -= Mod::get();
+    }
+    MontgomeryModInt::new_unchecked(sum)
+  }
+}
+
+impl<Mod: MontgomeryModulus> Sub for MontgomeryModInt<Mod> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self {
+    self + -rhs
+  }
+}
+
+impl<Mod: MontgomeryModulus> Mul for MontgomeryModInt<Mod> {
+  type Output = Self;
+
+  fn mul(self, rhs: Self) -> Self {
+    MontgomeryModInt::new_unchecked(Self::reduce(u128::from(self.mont) * u128::from(rhs.mont)))
+  }
+}
+
+impl<Mod: MontgomeryModulus> Div for MontgomeryModInt<Mod> {
+  type Output = Self;
+
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn div(self, rhs: Self) -> Self {
+    self * rhs.recip().expect("reciprocal does not exist")
+  }
+}
+
+impl<Mod: MontgomeryModulus> Neg for MontgomeryModInt<Mod> {
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    if self.mont == 0 {
+      self
+    } else {
+      MontgomeryModInt::new_unchecked(Mod::get() - self.mont)
+    }
+  }
+}
+
+impl<Mod: MontgomeryModulus> AddAssign for MontgomeryModInt<Mod> {
+  fn add_assign(&mut self, rhs: Self) {
+    *self = *self + rhs;
+  }
+}
+
+impl<Mod: MontgomeryModulus> SubAssign for MontgomeryModInt<Mod> {
+  fn sub_assign(&mut self, rhs: Self) {
+    *self = *self - rhs;
+  }
+}
+
+impl<Mod: MontgomeryModulus> MulAssign for MontgomeryModInt<Mod> {
+  fn mul_assign(&mut self, rhs: Self) {
+    *self = *self * rhs;
+  }
+}
+
+impl<Mod: MontgomeryModulus> DivAssign for MontgomeryModInt<Mod> {
+  fn div_assign(&mut self, rhs: Self) {
+    *self = *self / rhs;
+  }
+}
+
+impl<Mod: MontgomeryModulus> Sum for MontgomeryModInt<Mod> {
+  fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+    let mut acc = MontgomeryModInt::new_unchecked(0);
+    for x in iter {
+      acc += x;
+    }
+    acc
+  }
+}
+
+impl<Mod: MontgomeryModulus> Product for MontgomeryModInt<Mod> {
+  fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+    let mut acc = MontgomeryModInt::from_reduced(1);
+    for x in iter {
+      acc *= x;
+    }
+    acc
+  }
+}
+
+impl<Mod: MontgomeryModulus> Semiring for MontgomeryModInt<Mod> {
+  fn add(&self, rhs: &Self) -> Self {
+    *self + *rhs
+  }
+  fn mul(&self, rhs: &Self) -> Self {
+    *self * *rhs
+  }
+  fn zero() -> Self {
+    MontgomeryModInt::new_unchecked(0)
+  }
+  fn one() -> Self {
+    MontgomeryModInt::from_reduced(1)
+  }
+}
+
+impl<Mod: MontgomeryModulus> CommutativeSemiring for MontgomeryModInt<Mod> {}
+
+impl<Mod: MontgomeryModulus> Ring for MontgomeryModInt<Mod> {
+  fn neg(&self) -> Self {
+    -*self
+  }
+}
+
+impl<Mod: MontgomeryModulus> Field for MontgomeryModInt<Mod> {
+  fn recip(&self) -> Option<Self> {
+    MontgomeryModInt::recip(*self)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck_macros::quickcheck;
+  use std::convert::TryInto;
+
+  fn mod_repr(m: u64, x: i128) -> u64 {
+    x.rem_euclid(i128::from(m)).try_into().unwrap()
+  }
+
+  // Makes the modulus odd and less than 2^62, preferring large ones.
+  macro_rules! modulo {
+    ($m:ident) => {
+      let $m = $m >> 2 | 1;
+      if $m <= 1 {
+        return;
+      }
+      montgomery_modulus! {
+        static MOD: u64 = $m;
+      }
+    };
+  }
+
+  #[quickcheck]
+  fn from_prop(m: u64, x: i64) {
+    modulo!(m);
+    let a = MontgomeryModInt::<MOD>::from(x);
+    assert_eq!(a.repr(), mod_repr(m, i128::from(x)));
+  }
+
+  #[quickcheck]
+  fn new_prop(m: u64, x: u64) {
+    modulo!(m);
+    let a = MontgomeryModInt::<MOD>::new(x);
+    assert_eq!(a.repr(), x % m);
+    assert_eq!(ModInt::from(a), ModInt::<_, MOD>::new(x));
+    assert_eq!(MontgomeryModInt::from(ModInt::<_, MOD>::new(x)), a);
+  }
+
+  #[quickcheck]
+  fn recip_prop(m: u64, x: u64) {
+    modulo!(m);
+    let x = MontgomeryModInt::<MOD>::new(x);
+    match x.recip() {
+      Some(x_recip) => assert_eq!((x * x_recip).repr(), 1),
+      None => assert!(ModInt::<_, MOD>::from(x).recip().is_none()),
+    }
+  }
+
+  #[quickcheck]
+  fn ops_prop(m: u64, x: u64, y: u64) {
+    modulo!(m);
+    let a = MontgomeryModInt::<MOD>::new(x);
+    let b = MontgomeryModInt::<MOD>::new(y);
+    let (x, y) = (i128::from(x % m), i128::from(y % m));
+    assert_eq!((a + b).repr(), mod_repr(m, x + y));
+    assert_eq!((a - b).repr(), mod_repr(m, x - y));
+    assert_eq!((a * b).repr(), mod_repr(m, x * y));
+    assert_eq!((-a).repr(), mod_repr(m, -x));
+  }
+
+  #[quickcheck]
+  fn sum_product_prop(m: u64, xs: Vec<u64>) {
+    modulo!(m);
+    let a = xs.iter().copied().map(MontgomeryModInt::<MOD>::new);
+    let b = xs.iter().copied().map(ModInt::<_, MOD>::new);
+    assert_eq!(ModInt::from(a.clone().sum::<MontgomeryModInt<_>>()), b.clone().sum());
+    assert_eq!(ModInt::from(a.product::<MontgomeryModInt<_>>()), b.product());
+  }
+
+  #[test]
+  fn large_modulus() {
+    montgomery_modulus! {
+      const MOD: u64 = (1 << 62) - 57;
+    }
+    let x = MontgomeryModInt::<MOD>::new(MOD::get() - 1);
+    assert_eq!((x * x).repr(), 1);
+    assert_eq!((x + x).repr(), MOD::get() - 2);
+    assert_eq!(MontgomeryModInt::<MOD>::one().repr(), 1);
+    assert_eq!(MOD::get().wrapping_mul(MOD::neg_inv()), u64::max_value());
+    assert_eq!(
+      u128::from(MOD::r2()),
+      (1 << 124) % u128::from(MOD::get()) * 16 % u128::from(MOD::get())
+    );
+  }
+}
@@ -0,0 +1,461 @@
+use crate::algebra::structures::{CommutativeSemiring, Field, Ring, Semiring};
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A polynomial with coefficients in a ring.
+///
+/// The coefficients are stored in ascending order of degree, without trailing zeros. The
+/// multiplication is performed naively; see [`Fps`](crate::algebra::Fps) for fast operations
+/// modulo NTT-friendly primes.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::Polynomial;
+/// // x^2 - 3x + 2 = (x - 1)(x - 2)
+/// let f = Polynomial::new(vec![2i64, -3, 1]);
+/// assert_eq!(f.eval(&5), 12);
+/// assert_eq!(f.multipoint_eval(&[0, 1, 2, 3]), [2, 0, 0, 2]);
+///
+/// let (q, r) = f.div_rem_monic(&Polynomial::new(vec![-1, 1]));
+/// assert_eq!(q.coeffs(), [-2, 1]);
+/// assert!(r.is_zero());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Polynomial<R> {
+  coeffs: Vec<R>,
+}
+
+impl<R> Polynomial<R>
+where
+  R: Ring + PartialEq,
+{
+  /// Creates a new `Polynomial` with the given coefficients in ascending order of degree.
+  pub fn new(mut coeffs: Vec<R>) -> Self {
+    while coeffs.last().map_or(false, |c| *c == R::zero()) {
+      coeffs.pop();
+    }
+    Polynomial { coeffs }
+  }
+
+  /// Returns the coefficients in ascending order of degree, without trailing zeros.
+  pub fn coeffs(&self) -> &[R] {
+    &self.coeffs
+  }
+
+  /// Returns the coefficients in ascending order of degree, without trailing zeros.
+  pub fn into_coeffs(self) -> Vec<R> {
+    self.coeffs
+  }
+
+  /// Returns the coefficient of $x^i$.
+  pub fn coeff(&self, i: usize) -> R {
+    self.coeffs.get(i).cloned().unwrap_or_else(R::zero)
+  }
+
+  /// Returns the degree, or `None` if `self` is zero.
+  pub fn degree(&self) -> Option<usize> {
+    self.coeffs.len().checked_sub(1)
+  }
+
+  /// Returns whether `self` is zero.
+  pub fn is_zero(&self) -> bool {
+    self.coeffs.is_empty()
+  }
+
+  // `self` multiplied by the constant `c`
+  fn scale(&self, c: &R) -> Self {
+    Polynomial::new(self.coeffs.iter().map(|x| x.mul(c)).collect())
+  }
+
+  /// Evaluates `self` at `x` by Horner's method.
+  ///
+  /// # Time complexity
+  /// $O(n)$ where $n$ = `self.coeffs().len()`
+  pub fn eval(&self, x: &R) -> R {
+    self.coeffs.iter().rev().fold(R::zero(), |acc, c| acc.mul(x).add(c))
+  }
+
+  /// Returns the composition $f(g(x))$ where $f$ = `self` and $g$ = `g`.
+  ///
+  /// # Time complexity
+  /// $O(n^2 m^2)$ where $n$ = `self.coeffs().len()` and $m$ = `g.coeffs().len()`
+  pub fn compose(&self, g: &Self) -> Self {
+    self.coeffs.iter().rev().fold(Polynomial::zero(), |acc, c| {
+      Semiring::add(&Semiring::mul(&acc, g), &Polynomial::new(vec![c.clone()]))
+    })
+  }
+
+  /// Returns the quotient and the remainder of the polynomial long division by `divisor`, whose
+  /// leading coefficient must be one.
+  ///
+  /// # Panics
+  /// Panics if `divisor` is not monic.
+  ///
+  /// # Time complexity
+  /// $O(nm)$ where $n$ = `self.coeffs().len()` and $m$ = `divisor.coeffs().len()`
+  pub fn div_rem_monic(&self, divisor: &Self) -> (Self, Self) {
+    assert!(divisor.coeffs.last() == Some(&R::one()), "divisor must be monic");
+    let m = divisor.coeffs.len();
+    if self.coeffs.len() < m {
+      return (Polynomial::zero(), self.clone());
+    }
+    let mut rem = self.coeffs.clone();
+    let mut quot = vec![R::zero(); self.coeffs.len() - m + 1];
+    for i in (0..quot.len()).rev() {
+      let c = rem[i + m - 1].clone();
+      for (r, d) in rem[i..i + m].iter_mut().zip(&divisor.coeffs) {
+        *r = r.add(&c.mul(d).neg());
+      }
+      quot[i] = c;
+    }
+    rem.truncate(m - 1);
+    (Polynomial::new(quot), Polynomial::new(rem))
+  }
+
+  /// Evaluates `self` at each of `xs` by the remainder tree on the subproduct tree of
+  /// $x - x_i$.
+  ///
+  /// The multiplications and divisions are naive since `R` is an arbitrary ring; see
+  /// [`Fps::multipoint_eval`](crate::algebra::Fps::multipoint_eval) for the fast one modulo
+  /// NTT-friendly primes.
+  ///
+  /// # Time complexity
+  /// $O(nk + k^2)$ where $n$ = `self.coeffs().len()` and $k$ = `xs.len()`
+  ///
+  /// # References
+  ///
+  /// * [Polynomial evaluation - Wikipedia](https://en.wikipedia.org/w/index.php?title=Polynomial_evaluation&oldid=955437447#Multipoint_evaluation)
+  pub fn multipoint_eval(&self, xs: &[R]) -> Vec<R> {
+    // the subproduct tree, in which the node `k` has the children `2 * k` and `2 * k + 1`
+    fn build<R: Ring + PartialEq>(k: usize, xs: &[R], tree: &mut [Polynomial<R>]) {
+      if xs.len() == 1 {
+        tree[k] = Polynomial::new(vec![xs[0].neg(), R::one()]);
+        return;
+      }
+      let mid = xs.len() / 2;
+      build(2 * k, &xs[..mid], tree);
+      build(2 * k + 1, &xs[mid..], tree);
+      tree[k] = Semiring::mul(&tree[2 * k], &tree[2 * k + 1]);
+    }
+
+    fn descend<R: Ring + PartialEq>(
+      k: usize,
+      f: &Polynomial<R>,
+      len: usize,
+      tree: &[Polynomial<R>],
+      ys: &mut Vec<R>,
+    ) {
+      let (_, f) = f.div_rem_monic(&tree[k]);
+      if len == 1 {
+        ys.push(f.coeff(0));
+        return;
+      }
+      let mid = len / 2;
+      descend(2 * k, &f, mid, tree, ys);
+      descend(2 * k + 1, &f, len - mid, tree, ys);
+    }
+
+    if xs.is_empty() {
+      return vec![];
+    }
+    let mut tree = vec![Polynomial::zero(); 4 * xs.len()];
+    build(1, xs, &mut tree);
+    let mut ys = Vec::with_capacity(xs.len());
+    descend(1, self, xs.len(), &tree, &mut ys);
+    ys
+  }
+}
+
+impl<F> Polynomial<F>
+where
+  F: Field + PartialEq,
+{
+  /// Returns the quotient and the remainder of the polynomial long division by `divisor`.
+  ///
+  /// # Panics
+  /// Panics if `divisor` is zero, or its leading coefficient is not invertible.
+  ///
+  /// # Time complexity
+  /// $O(nm)$ where $n$ = `self.coeffs().len()` and $m$ = `divisor.coeffs().len()`
+  pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+    let lead = divisor.coeffs.last().expect("division by zero");
+    let inv = lead.recip().expect("leading coefficient is not invertible");
+    let (quot, rem) = self.div_rem_monic(&divisor.scale(&inv));
+    (quot.scale(&inv), rem)
+  }
+
+  /// Returns the polynomial of the minimum degree which takes `ys[i]` at `xs[i]` for each `i`, by
+  /// Lagrange interpolation.
+  ///
+  /// # Panics
+  /// Panics if `xs` and `ys` have different lengths, `xs` are not distinct, or a nonzero element
+  /// is not invertible.
+  ///
+  /// # Time complexity
+  /// $O(n^2)$ where $n$ = `xs.len()`
+  ///
+  /// # References
+  ///
+  /// * [Lagrange polynomial - Wikipedia](https://en.wikipedia.org/w/index.php?title=Lagrange_polynomial&oldid=955953826)
+  pub fn interpolate(xs: &[F], ys: &[F]) -> Self {
+    assert_eq!(xs.len(), ys.len(), "lengths of xs and ys must be equal");
+    let linear = |x: &F| Polynomial::new(vec![x.neg(), F::one()]);
+    // the product of x - x_i
+    let prod = xs.iter().fold(Polynomial::one(), |acc, x| Semiring::mul(&acc, &linear(x)));
+    xs.iter().zip(ys).fold(Polynomial::zero(), |acc, (x, y)| {
+      let (basis, _) = prod.div_rem_monic(&linear(x));
+      let w = basis.eval(x);
+      assert!(w != F::zero(), "xs must be distinct");
+      let w = w.recip().expect("nonzero element is not invertible (is the modulus a prime?)");
+      Semiring::add(&acc, &basis.scale(&y.mul(&w)))
+    })
+  }
+
+  /// Evaluates at `x` the polynomial of degree less than $n$ = `ys.len()` which takes `ys[i]`
+  /// at $i$ for each $0 \le i < n$.
+  ///
+  /// # Panics
+  /// Panics if the characteristic of `F` is positive and less than $n$, or a nonzero element is
+  /// not invertible.
+  ///
+  /// # Time complexity
+  /// $O(n)$
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use k7lib::algebra::{ModInt, Polynomial};
+  /// # use k7lib::constant;
+  /// constant! {
+  ///   const MOD: u32 = 998244353;
+  /// }
+  ///
+  /// // 0^2 + 1^2 + ... + x^2
+  /// let ys = [0, 1, 5, 14].iter().map(|&y| ModInt::<u32, MOD>::new(y)).collect::<Vec<_>>();
+  /// assert_eq!(Polynomial::eval_consecutive(&ys, &ModInt::new(100)).repr(), 338350);
+  /// ```
+  pub fn eval_consecutive(ys: &[F], x: &F) -> F {
+    let n = ys.len();
+    if n == 0 {
+      return F::zero();
+    }
+    // `ints[i]` = i
+    let mut ints = vec![F::zero(); n];
+    for i in 1..n {
+      ints[i] = ints[i - 1].add(&F::one());
+    }
+    // `left[i]` is the product of x - j for j < i, and `right[i]` is that for j > i.
+    let mut left = vec![F::one(); n];
+    for i in 1..n {
+      left[i] = left[i - 1].mul(&x.add(&ints[i - 1].neg()));
+    }
+    let mut right = vec![F::one(); n];
+    for i in (0..n - 1).rev() {
+      right[i] = right[i + 1].mul(&x.add(&ints[i + 1].neg()));
+    }
+    // `inv_fact[i]` = 1 / i!
+    let fact = ints.iter().skip(1).fold(F::one(), |acc, i| acc.mul(i));
+    assert!(fact != F::zero(), "characteristic must not be less than n");
+    let fact_inv =
+      fact.recip().expect("nonzero element is not invertible (is the modulus a prime?)");
+    let mut inv_fact = vec![fact_inv; n];
+    for i in (1..n).rev() {
+      inv_fact[i - 1] = inv_fact[i].mul(&ints[i]);
+    }
+    (0..n).fold(F::zero(), |acc, i| {
+      // the product of i - j for j != i is i! (n - 1 - i)! (-1)^(n - 1 - i)
+      let term = ys[i].mul(&left[i]).mul(&right[i]).mul(&inv_fact[i]).mul(&inv_fact[n - 1 - i]);
+      acc.add(&if (n - 1 - i) % 2 == 0 { term } else { term.neg() })
+    })
+  }
+}
+
+impl<R> Add for Polynomial<R>
+where
+  R: Ring + PartialEq,
+{
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    let (mut long, short) =
+      if self.coeffs.len() >= rhs.coeffs.len() { (self, rhs) } else { (rhs, self) };
+    for (x, y) in long.coeffs.iter_mut().zip(&short.coeffs) {
+      *x = x.add(y);
+    }
+    Polynomial::new(long.coeffs)
+  }
+}
+
+impl<R> Sub for Polynomial<R>
+where
+  R: Ring + PartialEq,
+{
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self {
+    self + -rhs
+  }
+}
+
+impl<R> Mul for Polynomial<R>
+where
+  R: Ring + PartialEq,
+{
+  type Output = Self;
+
+  fn mul(self, rhs: Self) -> Self {
+    if self.is_zero() || rhs.is_zero() {
+      return Polynomial::zero();
+    }
+    let mut coeffs = vec![R::zero(); self.coeffs.len() + rhs.coeffs.len() - 1];
+    for (i, x) in self.coeffs.iter().enumerate() {
+      for (j, y) in rhs.coeffs.iter().enumerate() {
+        coeffs[i + j] = coeffs[i + j].add(&x.mul(y));
+      }
+    }
+    Polynomial::new(coeffs)
+  }
+}
+
+impl<R> Neg for Polynomial<R>
+where
+  R: Ring + PartialEq,
+{
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    Polynomial { coeffs: self.coeffs.iter().map(R::neg).collect() }
+  }
+}
+
+impl<R> Semiring for Polynomial<R>
+where
+  R: Ring + PartialEq,
+{
+  fn add(&self, rhs: &Self) -> Self {
+    self.clone() + rhs.clone()
+  }
+  fn mul(&self, rhs: &Self) -> Self {
+    self.clone() * rhs.clone()
+  }
+  fn zero() -> Self {
+    Polynomial { coeffs: vec![] }
+  }
+  fn one() -> Self {
+    Polynomial::new(vec![R::one()])
+  }
+}
+
+impl<R> CommutativeSemiring for Polynomial<R> where R: Ring + CommutativeSemiring + PartialEq {}
+
+impl<R> Ring for Polynomial<R>
+where
+  R: Ring + PartialEq,
+{
+  fn neg(&self) -> Self {
+    -self.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::algebra::structures::Real;
+  use crate::algebra::ModInt;
+  use crate::constant;
+  use quickcheck_macros::quickcheck;
+
+  constant! {
+    const MOD: u32 = 998244353;
+  }
+
+  type Poly = Polynomial<ModInt<u32, MOD>>;
+
+  fn poly(xs: &[u32]) -> Poly {
+    Polynomial::new(xs.iter().map(|&x| ModInt::new(x)).collect())
+  }
+
+  #[quickcheck]
+  fn ops_prop(xs: Vec<u32>, ys: Vec<u32>, x: u32) {
+    let (f, g) = (poly(&xs), poly(&ys));
+    let x = ModInt::new(x);
+    assert_eq!((f.clone() + g.clone()).eval(&x), f.eval(&x) + g.eval(&x));
+    assert_eq!((f.clone() - g.clone()).eval(&x), f.eval(&x) - g.eval(&x));
+    assert_eq!((f.clone() * g.clone()).eval(&x), f.eval(&x) * g.eval(&x));
+    let h = poly(&ys[..ys.len().min(10)]);
+    assert_eq!(f.compose(&h).eval(&x), f.eval(&h.eval(&x)));
+    assert_eq!(f.clone() - f.clone(), Polynomial::zero());
+    assert!(f.coeffs().last().map_or(true, |&c| c != ModInt::new(0)));
+  }
+
+  #[quickcheck]
+  fn div_rem_prop(xs: Vec<u32>, ys: Vec<u32>) {
+    let (f, g) = (poly(&xs), poly(&ys));
+    if g.is_zero() {
+      return;
+    }
+    let (q, r) = f.div_rem(&g);
+    assert_eq!(q * g.clone() + r.clone(), f);
+    assert!(r.degree() < g.degree());
+  }
+
+  #[quickcheck]
+  fn div_rem_monic_prop(xs: Vec<i8>, ys: Vec<i8>) {
+    // Keeps the coefficients of the quotient small.
+    let f = Polynomial::new(xs.into_iter().take(10).map(i64::from).collect());
+    let mut ys = ys.into_iter().map(|y| i64::from(y % 3)).collect::<Vec<_>>();
+    ys.push(1);
+    let g = Polynomial::new(ys);
+    let (q, r) = f.div_rem_monic(&g);
+    assert_eq!(q * g.clone() + r.clone(), f);
+    assert!(r.degree() < g.degree());
+  }
+
+  #[quickcheck]
+  fn multipoint_eval_prop(xs: Vec<u32>, points: Vec<u32>) {
+    let f = poly(&xs);
+    let points = points.into_iter().map(ModInt::new).collect::<Vec<_>>();
+    let ys = points.iter().map(|x| f.eval(x)).collect::<Vec<_>>();
+    assert_eq!(f.multipoint_eval(&points), ys);
+  }
+
+  #[quickcheck]
+  fn interpolate_prop(xs: Vec<u32>) {
+    let f = poly(&xs);
+    // `xs.len()` distinct points determine `f` uniquely.
+    let points = (0..xs.len() as u32).map(|i| ModInt::new(i * 12345 + 678)).collect::<Vec<_>>();
+    let ys = points.iter().map(|x| f.eval(x)).collect::<Vec<_>>();
+    assert_eq!(Polynomial::interpolate(&points, &ys), f);
+  }
+
+  #[test]
+  #[should_panic(expected = "nonzero element is not invertible (is the modulus a prime?)")]
+  fn interpolate_composite() {
+    constant! {
+      const COMPOSITE: u32 = 4;
+    }
+    let m = |x: u32| ModInt::<u32, COMPOSITE>::new(x);
+    Polynomial::interpolate(&[m(0), m(2)], &[m(1), m(1)]);
+  }
+
+  #[quickcheck]
+  fn eval_consecutive_prop(xs: Vec<u32>, x: u32) {
+    let f = poly(&xs);
+    let ys = (0..xs.len() as u32).map(|i| f.eval(&ModInt::new(i))).collect::<Vec<_>>();
+    let x = ModInt::new(x);
+    assert_eq!(Polynomial::eval_consecutive(&ys, &x), f.eval(&x));
+  }
+
+  #[test]
+  fn interpolate_real() {
+    let xs = [0.0, 1.0, 2.0, 4.0].iter().map(|&x| Real(x)).collect::<Vec<_>>();
+    // 1 + x + x^2
+    let ys = xs.iter().map(|&Real(x)| Real(1.0 + x + x * x)).collect::<Vec<_>>();
+    let f = Polynomial::interpolate(&xs, &ys);
+    let coeffs = [1.0, 1.0, 1.0, 0.0];
+    assert!((0..4).all(|i| (f.coeff(i).0 - coeffs[i]).abs() < 1e-9));
+    let y = Polynomial::eval_consecutive(&ys[..3], &Real(10.0));
+    assert!((y.0 - 111.0).abs() < 1e-9);
+  }
+}
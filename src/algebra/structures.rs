@@ -2,6 +2,7 @@
 
 pub use self::commutative_semigroup::*;
 pub use self::commutative_semiring::*;
+pub use self::field::*;
 pub use self::group::*;
 pub use self::monoid::*;
 pub use self::ring::*;
@@ -10,6 +11,7 @@ pub use self::semiring::*;
 
 mod commutative_semigroup;
 mod commutative_semiring;
+mod field;
 mod group;
 mod monoid;
 mod ring;
@@ -22,8 +24,11 @@ pub use self::concat::*;
 pub use self::first::*;
 pub use self::last::*;
 pub use self::max::*;
+pub use self::max_plus::*;
 pub use self::min::*;
+pub use self::min_plus::*;
 pub use self::product::*;
+pub use self::real::*;
 pub use self::sum::*;
 
 mod all;
@@ -32,6 +37,9 @@ mod concat;
 mod first;
 mod last;
 mod max;
+mod max_plus;
 mod min;
+mod min_plus;
 mod product;
+mod real;
 mod sum;
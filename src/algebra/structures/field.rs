@@ -0,0 +1,24 @@
+use super::{CommutativeSemiring, Ring};
+
+/// A field.
+///
+/// # Laws
+/// * `Self::zero()` ≠ `Self::one()`
+/// * ∀`x` (`x.recip()` = `None` ⇔ `x` = `Self::zero()`)
+/// * ∀`x` (`x` ≠ `Self::zero()` ⇒ `x.mul(&x.recip().unwrap())` = `Self::one()`)
+///
+/// Modular integer types implement this trait for any modulus, but satisfy these laws only for
+/// prime moduli. Otherwise `recip` returns `None` for some nonzero elements, and the algorithms
+/// requiring a field panic on them.
+pub trait Field: Ring + CommutativeSemiring {
+  /// Returns the multiplicative inverse, or `None` if `self` is zero.
+  fn recip(&self) -> Option<Self>;
+
+  /// Performs division.
+  ///
+  /// # Panics
+  /// Panics if `rhs` is zero.
+  fn div(&self, rhs: &Self) -> Self {
+    self.mul(&rhs.recip().expect("division by zero"))
+  }
+}
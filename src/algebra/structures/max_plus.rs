@@ -0,0 +1,102 @@
+use crate::algebra::structures::{CommutativeSemiring, Semiring};
+use crate::cmp::Bounded;
+use crate::num::primitive::SignedInt as PrimSint;
+
+/// The max-plus (tropical) semiring, where addition is the maximum and multiplication is the sum.
+///
+/// [`Bounded::MIN`] is regarded as $-\infty$, which is the additive identity. The sum saturates,
+/// and the sum with $-\infty$ is always $-\infty$. The laws hold as long as no finite sum
+/// overflows. `T` must be signed, since $0$ would be $-\infty$ otherwise.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::Matrix;
+/// # use k7lib::algebra::structures::MaxPlus;
+/// let ninf = i64::min_value();
+/// let weight = vec![vec![ninf, 3, 1], vec![ninf, ninf, 4], vec![ninf, 2, ninf]];
+/// let rows = weight.into_iter().map(|row| row.into_iter().map(MaxPlus).collect()).collect();
+/// let a = Matrix::from_rows(rows);
+/// // the longest walks with exactly 2 edges
+/// let b = a.pow(2u32);
+/// assert_eq!(b[(0, 2)], MaxPlus(7));
+/// assert_eq!(b[(1, 0)], MaxPlus(ninf));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct MaxPlus<T>(pub T);
+
+/// Returns the additive identity $-\infty$.
+impl<T> Default for MaxPlus<T>
+where
+  T: PrimSint,
+{
+  fn default() -> Self {
+    Semiring::zero()
+  }
+}
+
+impl<T> Semiring for MaxPlus<T>
+where
+  T: PrimSint,
+{
+  fn add(&self, rhs: &Self) -> Self {
+    MaxPlus(self.0.max(rhs.0))
+  }
+  fn mul(&self, rhs: &Self) -> Self {
+    if self.0 == <T as Bounded>::MIN || rhs.0 == <T as Bounded>::MIN {
+      MaxPlus(<T as Bounded>::MIN)
+    } else {
+      MaxPlus(self.0.saturating_add(rhs.0))
+    }
+  }
+  fn zero() -> Self {
+    MaxPlus(<T as Bounded>::MIN)
+  }
+  fn one() -> Self {
+    MaxPlus(T::ZERO)
+  }
+}
+
+impl<T> CommutativeSemiring for MaxPlus<T> where T: PrimSint {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck_macros::quickcheck;
+
+  // small values, or the infinity
+  fn elem(x: i16) -> MaxPlus<i32> {
+    if x % 5 == 0 {
+      MaxPlus(i32::min_value())
+    } else {
+      MaxPlus(i32::from(x))
+    }
+  }
+
+  #[quickcheck]
+  fn semiring_prop(x: i16, y: i16, z: i16) {
+    let (x, y, z) = (elem(x), elem(y), elem(z));
+    assert_eq!(x.add(&y).add(&z), x.add(&y.add(&z)));
+    assert_eq!(x.add(&y), y.add(&x));
+    assert_eq!(x.mul(&y).mul(&z), x.mul(&y.mul(&z)));
+    assert_eq!(x.mul(&y), y.mul(&x));
+    assert_eq!(x.mul(&y.add(&z)), x.mul(&y).add(&x.mul(&z)));
+    assert_eq!(x.add(&MaxPlus::zero()), x);
+    assert_eq!(x.mul(&MaxPlus::one()), x);
+    assert_eq!(x.mul(&MaxPlus::zero()), MaxPlus::zero());
+  }
+
+  #[test]
+  fn saturating() {
+    let ninf = MaxPlus(i32::min_value());
+    assert_eq!(ninf.mul(&MaxPlus(5)), ninf);
+    assert_eq!(MaxPlus(i32::min_value() + 1).mul(&MaxPlus(-2)), ninf);
+    assert_eq!(MaxPlus(0i8).mul(&MaxPlus::one()), MaxPlus(0));
+    assert_eq!(MaxPlus(5i64).mul(&MaxPlus::one()), MaxPlus(5));
+  }
+
+  #[test]
+  fn default() {
+    assert_eq!(MaxPlus::<i32>::default(), Semiring::zero());
+  }
+}
@@ -0,0 +1,99 @@
+use crate::algebra::structures::{CommutativeSemiring, Semiring};
+use crate::cmp::Bounded;
+use crate::num::primitive::Int as PrimInt;
+
+/// The min-plus (tropical) semiring, where addition is the minimum and multiplication is the sum.
+///
+/// [`Bounded::MAX`] is regarded as $\infty$, which is the additive identity. The sum saturates,
+/// and the sum with $\infty$ is always $\infty$. The laws hold as long as no finite sum
+/// overflows.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::Matrix;
+/// # use k7lib::algebra::structures::MinPlus;
+/// let inf = u32::max_value();
+/// let dist = vec![vec![0, 3, inf], vec![inf, 0, 4], vec![1, inf, 0]];
+/// let rows = dist.into_iter().map(|row| row.into_iter().map(MinPlus).collect()).collect();
+/// let a = Matrix::from_rows(rows);
+/// // the shortest paths with at most 2 edges
+/// assert_eq!(a.pow(2u32)[(0, 2)], MinPlus(7));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct MinPlus<T>(pub T);
+
+/// Returns the additive identity $\infty$.
+impl<T> Default for MinPlus<T>
+where
+  T: PrimInt,
+{
+  fn default() -> Self {
+    Semiring::zero()
+  }
+}
+
+impl<T> Semiring for MinPlus<T>
+where
+  T: PrimInt,
+{
+  fn add(&self, rhs: &Self) -> Self {
+    MinPlus(self.0.min(rhs.0))
+  }
+  fn mul(&self, rhs: &Self) -> Self {
+    if self.0 == <T as Bounded>::MAX || rhs.0 == <T as Bounded>::MAX {
+      MinPlus(<T as Bounded>::MAX)
+    } else {
+      MinPlus(self.0.saturating_add(rhs.0))
+    }
+  }
+  fn zero() -> Self {
+    MinPlus(<T as Bounded>::MAX)
+  }
+  fn one() -> Self {
+    MinPlus(T::ZERO)
+  }
+}
+
+impl<T> CommutativeSemiring for MinPlus<T> where T: PrimInt {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck_macros::quickcheck;
+
+  // small values, or the infinity
+  fn elem(x: i16) -> MinPlus<i32> {
+    if x % 5 == 0 {
+      MinPlus(i32::max_value())
+    } else {
+      MinPlus(i32::from(x))
+    }
+  }
+
+  #[quickcheck]
+  fn semiring_prop(x: i16, y: i16, z: i16) {
+    let (x, y, z) = (elem(x), elem(y), elem(z));
+    assert_eq!(x.add(&y).add(&z), x.add(&y.add(&z)));
+    assert_eq!(x.add(&y), y.add(&x));
+    assert_eq!(x.mul(&y).mul(&z), x.mul(&y.mul(&z)));
+    assert_eq!(x.mul(&y), y.mul(&x));
+    assert_eq!(x.mul(&y.add(&z)), x.mul(&y).add(&x.mul(&z)));
+    assert_eq!(x.add(&MinPlus::zero()), x);
+    assert_eq!(x.mul(&MinPlus::one()), x);
+    assert_eq!(x.mul(&MinPlus::zero()), MinPlus::zero());
+  }
+
+  #[test]
+  fn saturating() {
+    let inf = MinPlus(i32::max_value());
+    assert_eq!(inf.mul(&MinPlus(-5)), inf);
+    assert_eq!(MinPlus(i32::max_value() - 1).mul(&MinPlus(2)), inf);
+    assert_eq!(MinPlus(5u32).mul(&MinPlus::one()), MinPlus(5));
+  }
+
+  #[test]
+  fn default() {
+    assert_eq!(MinPlus::<u32>::default(), Semiring::zero());
+  }
+}
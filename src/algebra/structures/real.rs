@@ -0,0 +1,51 @@
+use crate::algebra::structures::{CommutativeSemiring, Field, Ring, Semiring};
+
+/// A field of real numbers, approximated by `f64`.
+///
+/// The operations are subject to rounding errors, so the laws hold only approximately.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Default, Debug)]
+pub struct Real(pub f64);
+
+impl Semiring for Real {
+  fn add(&self, rhs: &Self) -> Self {
+    Self(self.0 + rhs.0)
+  }
+  fn mul(&self, rhs: &Self) -> Self {
+    Self(self.0 * rhs.0)
+  }
+  fn zero() -> Self {
+    Self(0.0)
+  }
+  fn one() -> Self {
+    Self(1.0)
+  }
+}
+
+impl CommutativeSemiring for Real {}
+
+impl Ring for Real {
+  fn neg(&self) -> Self {
+    Self(-self.0)
+  }
+}
+
+impl Field for Real {
+  fn recip(&self) -> Option<Self> {
+    if self.0 == 0.0 {
+      None
+    } else {
+      Some(Self(self.0.recip()))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test() {
+    assert_eq!(Real(3.0).div(&Real(4.0)).add(&Real(0.25).neg()), Real(0.5));
+    assert_eq!(Real(0.0).recip(), None);
+  }
+}
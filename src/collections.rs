@@ -0,0 +1,13 @@
+//! Miscellaneous data structures.
+
+pub use self::bit_set::*;
+pub use self::int_set::*;
+pub use self::leftist_heap::*;
+pub use self::radix_heap::*;
+pub use self::slope_trick::*;
+
+mod bit_set;
+mod int_set;
+mod leftist_heap;
+mod radix_heap;
+mod slope_trick;
@@ -0,0 +1,542 @@
+//! A bit set.
+
+use crate::num::div_ceil;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::iter::{FromIterator, FusedIterator};
+use std::ops::{
+  BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign, Shr,
+  ShrAssign,
+};
+
+const W: usize = 64;
+
+/// A fixed-length sequence of bits.
+///
+/// Shifts are performed towards larger indices (`<<`) or smaller indices (`>>`), and bits shifted
+/// out of $[0, n)$ are discarded.
+///
+/// # Examples
+///
+/// Subset sum:
+/// ```
+/// # use k7lib::collections::BitSet;
+/// let mut dp = BitSet::new(20);
+/// dp.set(0, true);
+/// for &a in &[3, 5, 7] {
+///   // dp |= dp << a
+///   dp.or_self_shl_assign(a);
+/// }
+/// assert_eq!(dp.iter().collect::<Vec<_>>(), [0, 3, 5, 7, 8, 10, 12, 15]);
+/// ```
+///
+/// # Space complexity
+/// $O(n)$ bits
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct BitSet {
+  // bits on `len..` in the last word are always zero
+  words: Vec<u64>,
+  len: usize,
+}
+
+impl BitSet {
+  /// Creates a new `BitSet` of the given length, filled with `false`.
+  ///
+  /// # Time complexity
+  /// $O(n / w)$
+  pub fn new(len: usize) -> Self {
+    Self { words: vec![0; div_ceil(len, W)], len }
+  }
+
+  /// Returns the number of bits.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns `true` iff the length is $0$.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns the bit at the given index.
+  ///
+  /// # Panics
+  /// Panics if `index` is out of bounds.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn get(&self, index: usize) -> bool {
+    assert_index(index, self.len());
+
+    self.words[index / W] >> (index % W) & 1 != 0
+  }
+
+  /// Sets the bit at the given index to `value`.
+  ///
+  /// # Panics
+  /// Panics if `index` is out of bounds.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn set(&mut self, index: usize, value: bool) {
+    assert_index(index, self.len());
+
+    if value {
+      self.words[index / W] |= 1 << (index % W);
+    } else {
+      self.words[index / W] &= !(1 << (index % W));
+    }
+  }
+
+  /// Flips the bit at the given index.
+  ///
+  /// # Panics
+  /// Panics if `index` is out of bounds.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn flip(&mut self, index: usize) {
+    assert_index(index, self.len());
+
+    self.words[index / W] ^= 1 << (index % W);
+  }
+
+  /// Sets all bits to `false`.
+  ///
+  /// # Time complexity
+  /// $O(n / w)$
+  pub fn clear(&mut self) {
+    for word in &mut self.words {
+      *word = 0;
+    }
+  }
+
+  /// Returns the number of `true` bits.
+  ///
+  /// # Time complexity
+  /// $O(n / w)$
+  pub fn count_ones(&self) -> usize {
+    self.words.iter().map(|word| word.count_ones() as usize).sum()
+  }
+
+  /// Returns the smallest index of `true` bits.
+  ///
+  /// # Time complexity
+  /// $O(n / w)$
+  pub fn find_first(&self) -> Option<usize> {
+    self.find_from(0)
+  }
+
+  /// Returns the smallest index of `true` bits greater than `index`.
+  ///
+  /// # Time complexity
+  /// $O(n / w)$
+  pub fn find_next(&self, index: usize) -> Option<usize> {
+    self.find_from(index.checked_add(1)?)
+  }
+
+  fn find_from(&self, index: usize) -> Option<usize> {
+    if index >= self.len() {
+      return None;
+    }
+    let i = index / W;
+    let word = self.words[i] >> (index % W);
+    if word != 0 {
+      return Some(index + word.trailing_zeros() as usize);
+    }
+    let j = i + 1 + self.words[i + 1..].iter().position(|&word| word != 0)?;
+    Some(j * W + self.words[j].trailing_zeros() as usize)
+  }
+
+  /// Returns an iterator over the indices of `true` bits in ascending order.
+  pub fn iter(&self) -> BitSetIter<'_> {
+    BitSetIter { set: self, next: self.find_first() }
+  }
+
+  fn mask_last(&mut self) {
+    if self.len % W != 0 {
+      *self.words.last_mut().unwrap() &= (1 << (self.len % W)) - 1;
+    }
+  }
+
+  // The `k`-th word of `self << n`.
+  fn shl_word(&self, n: usize, k: usize) -> u64 {
+    let (q, r) = (n / W, n % W);
+    let word = |i: Option<usize>| i.map_or(0, |i| self.words[i]);
+    let hi = word(k.checked_sub(q));
+    if r == 0 {
+      hi
+    } else {
+      let lo = word(k.checked_sub(q + 1));
+      hi << r | lo >> (W - r)
+    }
+  }
+
+  // The `k`-th word of `self >> n`.
+  fn shr_word(&self, n: usize, k: usize) -> u64 {
+    let (q, r) = (n / W, n % W);
+    let word = |i: usize| self.words.get(i).copied().unwrap_or(0);
+    let lo = word(k.saturating_add(q));
+    if r == 0 {
+      lo
+    } else {
+      let hi = word(k.saturating_add(q + 1));
+      lo >> r | hi << (W - r)
+    }
+  }
+
+  fn assert_same_len(&self, rhs: &Self) {
+    assert_eq!(self.len(), rhs.len(), "lengths of bit sets differ");
+  }
+}
+
+macro_rules! shifted_op_assign {
+  ($(
+    $op:tt,
+    $(#[$shl_attr:meta])* $shl:ident,
+    $(#[$shr_attr:meta])* $shr:ident,
+    $(#[$self_shl_attr:meta])* $self_shl:ident,
+    $(#[$self_shr_attr:meta])* $self_shr:ident;
+  )*) => {
+    impl BitSet {
+      $(
+        $(#[$shl_attr])*
+        ///
+        /// # Panics
+        /// Panics if `self.len() != rhs.len()`.
+        ///
+        /// # Time complexity
+        /// $O(n / w)$
+        pub fn $shl(&mut self, rhs: &Self, n: usize) {
+          self.assert_same_len(rhs);
+          for k in 0..self.words.len() {
+            let word = rhs.shl_word(n, k);
+            self.words[k] $op word;
+          }
+          self.mask_last();
+        }
+
+        $(#[$shr_attr])*
+        ///
+        /// # Panics
+        /// Panics if `self.len() != rhs.len()`.
+        ///
+        /// # Time complexity
+        /// $O(n / w)$
+        pub fn $shr(&mut self, rhs: &Self, n: usize) {
+          self.assert_same_len(rhs);
+          for k in 0..self.words.len() {
+            let word = rhs.shr_word(n, k);
+            self.words[k] $op word;
+          }
+        }
+
+        $(#[$self_shl_attr])*
+        ///
+        /// # Time complexity
+        /// $O(n / w)$
+        pub fn $self_shl(&mut self, n: usize) {
+          // The `k`-th word of `self << n` depends only on words at indices `..=k`.
+          for k in (0..self.words.len()).rev() {
+            let word = self.shl_word(n, k);
+            self.words[k] $op word;
+          }
+          self.mask_last();
+        }
+
+        $(#[$self_shr_attr])*
+        ///
+        /// # Time complexity
+        /// $O(n / w)$
+        pub fn $self_shr(&mut self, n: usize) {
+          // The `k`-th word of `self >> n` depends only on words at indices `k..`.
+          for k in 0..self.words.len() {
+            let word = self.shr_word(n, k);
+            self.words[k] $op word;
+          }
+        }
+      )*
+    }
+  };
+}
+
+shifted_op_assign! {
+  &=,
+  /// Assigns `self & (rhs << n)` to `self` without allocation.
+  and_shl_assign,
+  /// Assigns `self & (rhs >> n)` to `self` without allocation.
+  and_shr_assign,
+  /// Assigns `self & (self << n)` to `self` without allocation.
+  and_self_shl_assign,
+  /// Assigns `self & (self >> n)` to `self` without allocation.
+  and_self_shr_assign;
+
+  |=,
+  /// Assigns `self | (rhs << n)` to `self` without allocation.
+  or_shl_assign,
+  /// Assigns `self | (rhs >> n)` to `self` without allocation.
+  or_shr_assign,
+  /// Assigns `self | (self << n)` to `self` without allocation.
+  or_self_shl_assign,
+  /// Assigns `self | (self >> n)` to `self` without allocation.
+  or_self_shr_assign;
+
+  ^=,
+  /// Assigns `self ^ (rhs << n)` to `self` without allocation.
+  xor_shl_assign,
+  /// Assigns `self ^ (rhs >> n)` to `self` without allocation.
+  xor_shr_assign,
+  /// Assigns `self ^ (self << n)` to `self` without allocation.
+  xor_self_shl_assign,
+  /// Assigns `self ^ (self >> n)` to `self` without allocation.
+  xor_self_shr_assign;
+}
+
+impl ShlAssign<usize> for BitSet {
+  fn shl_assign(&mut self, n: usize) {
+    for k in (0..self.words.len()).rev() {
+      self.words[k] = self.shl_word(n, k);
+    }
+    self.mask_last();
+  }
+}
+
+impl ShrAssign<usize> for BitSet {
+  fn shr_assign(&mut self, n: usize) {
+    for k in 0..self.words.len() {
+      self.words[k] = self.shr_word(n, k);
+    }
+  }
+}
+
+impl Shl<usize> for &BitSet {
+  type Output = BitSet;
+
+  fn shl(self, n: usize) -> BitSet {
+    let mut set = self.clone();
+    set <<= n;
+    set
+  }
+}
+
+impl Shr<usize> for &BitSet {
+  type Output = BitSet;
+
+  fn shr(self, n: usize) -> BitSet {
+    let mut set = self.clone();
+    set >>= n;
+    set
+  }
+}
+
+macro_rules! bit_op {
+  ($($Op:ident $op:ident $OpAssign:ident $op_assign:ident)*) => {$(
+    impl $OpAssign<&BitSet> for BitSet {
+      fn $op_assign(&mut self, rhs: &BitSet) {
+        self.assert_same_len(rhs);
+        for (lhs, rhs) in self.words.iter_mut().zip(&rhs.words) {
+          lhs.$op_assign(rhs);
+        }
+      }
+    }
+
+    impl $Op for &BitSet {
+      type Output = BitSet;
+
+      fn $op(self, rhs: &BitSet) -> BitSet {
+        let mut set = self.clone();
+        set.$op_assign(rhs);
+        set
+      }
+    }
+  )*};
+}
+
+bit_op! {
+  BitAnd bitand BitAndAssign bitand_assign
+  BitOr bitor BitOrAssign bitor_assign
+  BitXor bitxor BitXorAssign bitxor_assign
+}
+
+impl Not for &BitSet {
+  type Output = BitSet;
+
+  fn not(self) -> BitSet {
+    let mut set = BitSet { words: self.words.iter().map(|word| !word).collect(), len: self.len };
+    set.mask_last();
+    set
+  }
+}
+
+impl FromIterator<bool> for BitSet {
+  fn from_iter<I>(iter: I) -> Self
+  where
+    I: IntoIterator<Item = bool>,
+  {
+    let mut words = vec![];
+    let mut len = 0;
+    for bit in iter {
+      if len % W == 0 {
+        words.push(0);
+      }
+      *words.last_mut().unwrap() |= u64::from(bit) << (len % W);
+      len += 1;
+    }
+    Self { words, len }
+  }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+  type Item = usize;
+  type IntoIter = BitSetIter<'a>;
+
+  fn into_iter(self) -> BitSetIter<'a> {
+    self.iter()
+  }
+}
+
+/// An iterator created by [`BitSet::iter`].
+#[derive(Clone, Debug)]
+pub struct BitSetIter<'a> {
+  set: &'a BitSet,
+  next: Option<usize>,
+}
+
+impl Iterator for BitSetIter<'_> {
+  type Item = usize;
+
+  fn next(&mut self) -> Option<usize> {
+    let index = self.next?;
+    self.next = self.set.find_next(index);
+    Some(index)
+  }
+}
+
+impl FusedIterator for BitSetIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck_macros::quickcheck;
+
+  fn naive_shl(s: &[bool], n: usize) -> Vec<bool> {
+    (0..s.len()).map(|i| i >= n && s[i - n]).collect()
+  }
+
+  fn naive_shr(s: &[bool], n: usize) -> Vec<bool> {
+    (0..s.len()).map(|i| i.checked_add(n).map_or(false, |j| j < s.len() && s[j])).collect()
+  }
+
+  fn to_vec(set: &BitSet) -> Vec<bool> {
+    (0..set.len()).map(|i| set.get(i)).collect()
+  }
+
+  fn zip_with(s: &[bool], t: &[bool], f: impl Fn(bool, bool) -> bool) -> Vec<bool> {
+    s.iter().zip(t).map(|(&x, &y)| f(x, y)).collect()
+  }
+
+  #[quickcheck]
+  fn get_set_prop(s: Vec<bool>, ops: Vec<(usize, bool)>) {
+    let mut set = s.iter().copied().collect::<BitSet>();
+    let mut naive = s;
+    assert_eq!(set.len(), naive.len());
+    for (i, value) in ops {
+      if naive.is_empty() {
+        break;
+      }
+      let i = i % naive.len();
+      set.set(i, value);
+      naive[i] = value;
+      set.flip(i);
+      naive[i] ^= true;
+    }
+    assert_eq!(to_vec(&set), naive);
+    assert_eq!(set.count_ones(), naive.iter().filter(|&&x| x).count());
+  }
+
+  #[quickcheck]
+  fn find_prop(s: Vec<bool>, i: usize) {
+    let set = s.iter().copied().collect::<BitSet>();
+    let ones = (0..s.len()).filter(|&i| s[i]).collect::<Vec<_>>();
+    assert_eq!(set.find_first(), ones.first().copied());
+    assert_eq!(set.find_next(i), ones.iter().copied().find(|&j| j > i));
+    assert_eq!(set.iter().collect::<Vec<_>>(), ones);
+  }
+
+  #[quickcheck]
+  fn shift_prop(s: Vec<bool>, n: u8) {
+    let n = usize::from(n);
+    let set = s.iter().copied().collect::<BitSet>();
+    assert_eq!(to_vec(&(&set << n)), naive_shl(&s, n));
+    assert_eq!(to_vec(&(&set >> n)), naive_shr(&s, n));
+    assert_eq!((&set << n).count_ones(), naive_shl(&s, n).iter().filter(|&&x| x).count());
+    assert_eq!(to_vec(&(&set >> usize::max_value())), vec![false; s.len()]);
+    assert_eq!(to_vec(&(&set << usize::max_value())), vec![false; s.len()]);
+  }
+
+  #[test]
+  fn long_shift() {
+    let s = (0..1000).map(|i| i * i % 7 < 3).collect::<Vec<_>>();
+    let set = s.iter().copied().collect::<BitSet>();
+    for n in (0..1100).step_by(13) {
+      assert_eq!(to_vec(&(&set << n)), naive_shl(&s, n));
+      assert_eq!(to_vec(&(&set >> n)), naive_shr(&s, n));
+
+      let mut t = set.clone();
+      t.or_self_shl_assign(n);
+      assert_eq!(to_vec(&t), zip_with(&s, &naive_shl(&s, n), |x, y| x | y));
+
+      let mut t = set.clone();
+      t.xor_self_shr_assign(n);
+      assert_eq!(to_vec(&t), zip_with(&s, &naive_shr(&s, n), |x, y| x ^ y));
+    }
+  }
+
+  #[quickcheck]
+  fn bit_op_prop(st: Vec<(bool, bool)>) {
+    let s = st.iter().map(|&(x, _)| x).collect::<Vec<_>>();
+    let t = st.iter().map(|&(_, y)| y).collect::<Vec<_>>();
+    let a = s.iter().copied().collect::<BitSet>();
+    let b = t.iter().copied().collect::<BitSet>();
+    assert_eq!(to_vec(&(&a & &b)), zip_with(&s, &t, |x, y| x & y));
+    assert_eq!(to_vec(&(&a | &b)), zip_with(&s, &t, |x, y| x | y));
+    assert_eq!(to_vec(&(&a ^ &b)), zip_with(&s, &t, |x, y| x ^ y));
+    assert_eq!(to_vec(&!&a), s.iter().map(|&x| !x).collect::<Vec<_>>());
+    assert_eq!((!&a).count_ones(), s.iter().filter(|&&x| !x).count());
+  }
+
+  #[quickcheck]
+  fn shifted_op_assign_prop(st: Vec<(bool, bool)>, n: u8) {
+    let n = usize::from(n);
+    let s = st.iter().map(|&(x, _)| x).collect::<Vec<_>>();
+    let t = st.iter().map(|&(_, y)| y).collect::<Vec<_>>();
+    let a = s.iter().copied().collect::<BitSet>();
+    let b = t.iter().copied().collect::<BitSet>();
+
+    macro_rules! check {
+      ($method:ident, $self_method:ident, $naive_shift:ident, $op:tt) => {
+        let mut c = a.clone();
+        c.$method(&b, n);
+        assert_eq!(to_vec(&c), zip_with(&s, &$naive_shift(&t, n), |x, y| x $op y));
+
+        let mut c = a.clone();
+        c.$self_method(n);
+        assert_eq!(to_vec(&c), zip_with(&s, &$naive_shift(&s, n), |x, y| x $op y));
+        assert_eq!(c.count_ones(), to_vec(&c).iter().filter(|&&x| x).count());
+      };
+    }
+
+    check!(and_shl_assign, and_self_shl_assign, naive_shl, &);
+    check!(and_shr_assign, and_self_shr_assign, naive_shr, &);
+    check!(or_shl_assign, or_self_shl_assign, naive_shl, |);
+    check!(or_shr_assign, or_self_shr_assign, naive_shr, |);
+    check!(xor_shl_assign, xor_self_shl_assign, naive_shl, ^);
+    check!(xor_shr_assign, xor_self_shr_assign, naive_shr, ^);
+  }
+}
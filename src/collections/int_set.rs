@@ -0,0 +1,311 @@
+//! A set of integers with fast successor and predecessor queries.
+
+use crate::num::div_ceil;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::iter::FusedIterator;
+
+const W: usize = 64;
+
+/// A set of integers in $[0, n)$, represented by a 64-ary tree of bit words.
+///
+/// Each bit of a word on a layer indicates whether the corresponding word on the layer below is
+/// non-zero, so the successor and predecessor queries can skip empty ranges word by word.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::collections::IntSet;
+/// let mut set = IntSet::new(1000);
+/// set.insert(3);
+/// set.insert(500);
+///
+/// assert_eq!(set.next(4), Some(500));
+/// assert_eq!(set.prev(499), Some(3));
+/// assert_eq!(set.next(501), None);
+///
+/// set.remove(500);
+/// assert_eq!(set.next(4), None);
+/// ```
+///
+/// # Space complexity
+/// $O(n)$ bits
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct IntSet {
+  // `layers[0]` is the bottom layer and `layers.last()` consists of a single word
+  layers: Vec<Vec<u64>>,
+  universe_len: usize,
+  len: usize,
+}
+
+impl IntSet {
+  /// Creates an empty `IntSet` which can contain integers in $[0, n)$, where $n$ = `universe_len`.
+  ///
+  /// # Time complexity
+  /// $O(n / w)$
+  pub fn new(universe_len: usize) -> Self {
+    let mut layers = vec![];
+    let mut len = universe_len;
+    loop {
+      let words = div_ceil(len, W);
+      if words <= 1 {
+        layers.push(vec![0; 1]);
+        break;
+      }
+      layers.push(vec![0; words]);
+      len = words;
+    }
+    Self { layers, universe_len, len: 0 }
+  }
+
+  /// Returns $n$, where the integers in the set must be in $[0, n)$.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn universe_len(&self) -> usize {
+    self.universe_len
+  }
+
+  /// Returns the number of elements in the set.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns `true` iff the set contains no elements.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns `true` iff the set contains `x`.
+  ///
+  /// # Panics
+  /// Panics if `x` is out of bounds.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn contains(&self, x: usize) -> bool {
+    assert_index(x, self.universe_len());
+
+    self.layers[0][x / W] >> (x % W) & 1 != 0
+  }
+
+  /// Adds `x` to the set.
+  ///
+  /// Returns `false` iff the set already contains `x`.
+  ///
+  /// # Panics
+  /// Panics if `x` is out of bounds.
+  ///
+  /// # Time complexity
+  /// $O(\log_w(n))$
+  pub fn insert(&mut self, x: usize) -> bool {
+    if self.contains(x) {
+      return false;
+    }
+    let mut x = x;
+    for layer in &mut self.layers {
+      let word = &mut layer[x / W];
+      let was_empty = *word == 0;
+      *word |= 1 << (x % W);
+      if !was_empty {
+        break;
+      }
+      x /= W;
+    }
+    self.len += 1;
+    true
+  }
+
+  /// Removes `x` from the set.
+  ///
+  /// Returns `false` iff the set does not contain `x`.
+  ///
+  /// # Panics
+  /// Panics if `x` is out of bounds.
+  ///
+  /// # Time complexity
+  /// $O(\log_w(n))$
+  pub fn remove(&mut self, x: usize) -> bool {
+    if !self.contains(x) {
+      return false;
+    }
+    let mut x = x;
+    for layer in &mut self.layers {
+      let word = &mut layer[x / W];
+      *word &= !(1 << (x % W));
+      if *word != 0 {
+        break;
+      }
+      x /= W;
+    }
+    self.len -= 1;
+    true
+  }
+
+  /// Returns the smallest element greater than or equal to `x`.
+  ///
+  /// # Time complexity
+  /// $O(\log_w(n))$
+  pub fn next(&self, x: usize) -> Option<usize> {
+    if x >= self.universe_len() {
+      return None;
+    }
+    let mut x = x;
+    for h in 0..self.layers.len() {
+      let i = x / W;
+      if i >= self.layers[h].len() {
+        return None;
+      }
+      let word = self.layers[h][i] >> (x % W);
+      if word != 0 {
+        x += word.trailing_zeros() as usize;
+        // Descends to the bottom layer through the smallest non-empty words.
+        for layer in self.layers[..h].iter().rev() {
+          x = x * W + layer[x].trailing_zeros() as usize;
+        }
+        return Some(x);
+      }
+      x = i + 1;
+    }
+    None
+  }
+
+  /// Returns the largest element less than or equal to `x`.
+  ///
+  /// # Time complexity
+  /// $O(\log_w(n))$
+  pub fn prev(&self, x: usize) -> Option<usize> {
+    if self.universe_len() == 0 {
+      return None;
+    }
+    let mut x = x.min(self.universe_len() - 1);
+    for h in 0..self.layers.len() {
+      let i = x / W;
+      let word = self.layers[h][i] << (W - 1 - x % W);
+      if word != 0 {
+        x -= word.leading_zeros() as usize;
+        // Descends to the bottom layer through the largest non-empty words.
+        for layer in self.layers[..h].iter().rev() {
+          x = x * W + W - 1 - layer[x].leading_zeros() as usize;
+        }
+        return Some(x);
+      }
+      if i == 0 {
+        return None;
+      }
+      x = i - 1;
+    }
+    None
+  }
+
+  /// Returns the smallest element.
+  ///
+  /// # Time complexity
+  /// $O(\log_w(n))$
+  pub fn min(&self) -> Option<usize> {
+    self.next(0)
+  }
+
+  /// Returns the largest element.
+  ///
+  /// # Time complexity
+  /// $O(\log_w(n))$
+  pub fn max(&self) -> Option<usize> {
+    self.prev(usize::max_value())
+  }
+
+  /// Returns an iterator over the elements in ascending order.
+  pub fn iter(&self) -> IntSetIter<'_> {
+    IntSetIter { set: self, next: self.min() }
+  }
+}
+
+impl<'a> IntoIterator for &'a IntSet {
+  type Item = usize;
+  type IntoIter = IntSetIter<'a>;
+
+  fn into_iter(self) -> IntSetIter<'a> {
+    self.iter()
+  }
+}
+
+/// An iterator created by [`IntSet::iter`].
+#[derive(Clone, Debug)]
+pub struct IntSetIter<'a> {
+  set: &'a IntSet,
+  next: Option<usize>,
+}
+
+impl Iterator for IntSetIter<'_> {
+  type Item = usize;
+
+  fn next(&mut self) -> Option<usize> {
+    let x = self.next?;
+    self.next = self.set.next(x + 1);
+    Some(x)
+  }
+}
+
+impl FusedIterator for IntSetIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck_macros::quickcheck;
+  use std::collections::BTreeSet;
+
+  #[quickcheck]
+  fn prop(len: u16, ops: Vec<(bool, u16)>, queries: Vec<u16>) {
+    let len = usize::from(len) % 5000;
+    let mut set = IntSet::new(len);
+    let mut naive = BTreeSet::new();
+
+    assert_eq!(set.universe_len(), len);
+
+    for (insert, x) in ops {
+      if len == 0 {
+        break;
+      }
+      let x = usize::from(x) % len;
+      if insert {
+        assert_eq!(set.insert(x), naive.insert(x));
+      } else {
+        assert_eq!(set.remove(x), naive.remove(&x));
+      }
+      assert_eq!(set.len(), naive.len());
+    }
+
+    for x in queries {
+      let x = usize::from(x);
+      if x < len {
+        assert_eq!(set.contains(x), naive.contains(&x));
+      }
+      assert_eq!(set.next(x), naive.range(x..).next().copied());
+      assert_eq!(set.prev(x), naive.range(..=x).next_back().copied());
+    }
+
+    assert_eq!(set.min(), naive.iter().next().copied());
+    assert_eq!(set.max(), naive.iter().next_back().copied());
+    assert!(set.iter().eq(naive.iter().copied()));
+  }
+
+  #[test]
+  fn dense() {
+    let len = W * W * W + 1;
+    let mut set = IntSet::new(len);
+    for x in (0..len).step_by(W * W - 1) {
+      set.insert(x);
+    }
+    let naive = (0..len).step_by(W * W - 1).collect::<BTreeSet<_>>();
+    for x in (0..len).step_by(97) {
+      assert_eq!(set.next(x), naive.range(x..).next().copied());
+      assert_eq!(set.prev(x), naive.range(..=x).next_back().copied());
+    }
+  }
+}
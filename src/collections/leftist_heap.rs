@@ -0,0 +1,299 @@
+//! A leftist heap.
+
+use crate::num::primitive::Int as PrimInt;
+
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+
+/// A meldable min-heap based on a leftist tree, supporting lazy addition to all keys.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::collections::LeftistHeap;
+/// let mut a = LeftistHeap::new();
+/// a.push(3, 'a');
+/// a.push(1, 'b');
+///
+/// let mut b = LeftistHeap::new();
+/// b.push(4, 'c');
+/// b.add_all(-2);
+///
+/// a.append(&mut b);
+/// assert!(b.is_empty());
+///
+/// assert_eq!(a.pop(), Some((1, 'b')));
+/// assert_eq!(a.pop(), Some((2, 'c')));
+/// assert_eq!(a.pop(), Some((3, 'a')));
+/// assert_eq!(a.pop(), None);
+/// ```
+///
+/// # References
+///
+/// * [Leftist tree - Wikipedia][1]
+///
+/// [1]: https://en.wikipedia.org/w/index.php?title=Leftist_tree&oldid=989532183
+pub struct LeftistHeap<K, V> {
+  root: Link<K, V>,
+  len: usize,
+}
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+  key: K,
+  value: V,
+  // to be added to all keys in the subtrees
+  lazy: K,
+  // the length of the right spine
+  rank: usize,
+  left: Link<K, V>,
+  right: Link<K, V>,
+}
+
+impl<K, V> Node<K, V>
+where
+  K: PrimInt,
+{
+  fn add(&mut self, x: K) {
+    self.key += x;
+    self.lazy += x;
+  }
+
+  fn push_down(&mut self) {
+    if self.lazy != K::ZERO {
+      if let Some(left) = &mut self.left {
+        left.add(self.lazy);
+      }
+      if let Some(right) = &mut self.right {
+        right.add(self.lazy);
+      }
+      self.lazy = K::ZERO;
+    }
+  }
+}
+
+fn rank<K, V>(link: &Link<K, V>) -> usize {
+  link.as_ref().map_or(0, |node| node.rank)
+}
+
+fn meld<K, V>(a: Link<K, V>, b: Link<K, V>) -> Link<K, V>
+where
+  K: PrimInt,
+{
+  let (mut a, mut b) = match (a, b) {
+    (Some(a), Some(b)) => (a, b),
+    (a, None) => return a,
+    (None, b) => return b,
+  };
+  if a.key > b.key {
+    mem::swap(&mut a, &mut b);
+  }
+  a.push_down();
+  // The right spine has O(log n) nodes, so the recursion is shallow.
+  a.right = meld(a.right.take(), Some(b));
+  if rank(&a.left) < rank(&a.right) {
+    mem::swap(&mut a.left, &mut a.right);
+  }
+  a.rank = rank(&a.right) + 1;
+  Some(a)
+}
+
+impl<K, V> Default for LeftistHeap<K, V> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K, V> LeftistHeap<K, V> {
+  /// Creates an empty `LeftistHeap`.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn new() -> Self {
+    Self { root: None, len: 0 }
+  }
+
+  /// Returns the number of elements.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns `true` iff the heap contains no elements.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+}
+
+impl<K, V> LeftistHeap<K, V>
+where
+  K: PrimInt,
+{
+  /// Returns the element with the smallest key.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn peek(&self) -> Option<(K, &V)> {
+    self.root.as_ref().map(|root| (root.key, &root.value))
+  }
+
+  /// Pushes an element.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn push(&mut self, key: K, value: V) {
+    let node = Node { key, value, lazy: K::ZERO, rank: 1, left: None, right: None };
+    self.root = meld(self.root.take(), Some(Box::new(node)));
+    self.len += 1;
+  }
+
+  /// Removes the element with the smallest key and returns it.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn pop(&mut self) -> Option<(K, V)> {
+    let mut root = self.root.take()?;
+    root.push_down();
+    self.root = meld(root.left.take(), root.right.take());
+    self.len -= 1;
+    Some((root.key, root.value))
+  }
+
+  /// Moves all elements of `other` into `self`, leaving `other` empty.
+  ///
+  /// # Time complexity
+  /// $O(\log(n + m))$ where $m$ = `other.len()`
+  pub fn append(&mut self, other: &mut Self) {
+    self.root = meld(self.root.take(), other.root.take());
+    self.len += mem::replace(&mut other.len, 0);
+  }
+
+  /// Adds `x` to the keys of all elements.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn add_all(&mut self, x: K) {
+    if let Some(root) = &mut self.root {
+      root.add(x);
+    }
+  }
+}
+
+impl<K, V> Drop for LeftistHeap<K, V> {
+  fn drop(&mut self) {
+    // Avoids the recursive drop, since the left spine may be long.
+    let mut stack = self.root.take().into_iter().collect::<Vec<_>>();
+    while let Some(mut node) = stack.pop() {
+      stack.extend(node.left.take());
+      stack.extend(node.right.take());
+    }
+  }
+}
+
+impl<K, V> Debug for LeftistHeap<K, V>
+where
+  K: PrimInt,
+  V: Debug,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let mut list = f.debug_list();
+    let mut stack = self.root.iter().map(|root| (root, K::ZERO)).collect::<Vec<_>>();
+    while let Some((node, lazy)) = stack.pop() {
+      list.entry(&(node.key + lazy, &node.value));
+      let lazy = lazy + node.lazy;
+      stack.extend(node.left.iter().map(|left| (left, lazy)));
+      stack.extend(node.right.iter().map(|right| (right, lazy)));
+    }
+    list.finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck::{Arbitrary, Gen};
+  use quickcheck_macros::quickcheck;
+  use rand::Rng as _;
+
+  const HEAPS_LEN: usize = 3;
+
+  #[derive(Clone, Debug)]
+  enum Op {
+    Push(usize, i32, u8),
+    Pop(usize),
+    Append(usize, usize),
+    AddAll(usize, i32),
+  }
+
+  impl Arbitrary for Op {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      let i = g.gen_range(0, HEAPS_LEN);
+      match g.gen_range(0, 4) {
+        0 => Op::Push(i, g.gen_range(-100, 100), g.gen()),
+        1 => Op::Pop(i),
+        2 => Op::Append(i, g.gen_range(0, HEAPS_LEN)),
+        _ => Op::AddAll(i, g.gen_range(-100, 100)),
+      }
+    }
+  }
+
+  #[quickcheck]
+  fn prop(ops: Vec<Op>) {
+    let mut heaps = (0..HEAPS_LEN).map(|_| LeftistHeap::new()).collect::<Vec<_>>();
+    let mut naive = vec![vec![]; HEAPS_LEN];
+
+    for op in ops {
+      match op {
+        Op::Push(i, key, value) => {
+          heaps[i].push(key, value);
+          naive[i].push((key, value));
+        }
+        Op::Pop(i) => match heaps[i].pop() {
+          Some((key, value)) => {
+            assert_eq!(Some(key), naive[i].iter().map(|&(key, _)| key).min());
+            let pos = naive[i].iter().position(|&e| e == (key, value)).unwrap();
+            naive[i].swap_remove(pos);
+          }
+          None => assert!(naive[i].is_empty()),
+        },
+        Op::Append(i, j) => {
+          if i != j {
+            let mut other = mem::replace(&mut heaps[j], LeftistHeap::new());
+            heaps[i].append(&mut other);
+            assert!(other.is_empty());
+            let other = mem::replace(&mut naive[j], vec![]);
+            naive[i].extend(other);
+          }
+        }
+        Op::AddAll(i, x) => {
+          heaps[i].add_all(x);
+          for (key, _) in &mut naive[i] {
+            *key += x;
+          }
+        }
+      }
+
+      for (heap, naive) in heaps.iter().zip(&naive) {
+        assert_eq!(heap.len(), naive.len());
+        assert_eq!(heap.peek().map(|(key, _)| key), naive.iter().map(|&(key, _)| key).min());
+      }
+    }
+  }
+
+  #[test]
+  fn long_left_spine() {
+    let mut heap = LeftistHeap::new();
+    for i in (0..1_000_000).rev() {
+      heap.push(i, ());
+    }
+    for i in 0..10 {
+      assert_eq!(heap.pop(), Some((i, ())));
+    }
+  }
+}
@@ -0,0 +1,179 @@
+//! A radix heap.
+
+use crate::num::primitive::UnsignedInt as PrimUint;
+
+use std::mem;
+
+/// A monotone min-heap with unsigned integer keys.
+///
+/// The key of a pushed element must not be less than the key of the last popped element,
+/// which is always satisfied in Dijkstra's algorithm.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::collections::RadixHeap;
+/// let mut heap = RadixHeap::new();
+/// heap.push(5u32, 'a');
+/// heap.push(2, 'b');
+///
+/// assert_eq!(heap.pop(), Some((2, 'b')));
+/// heap.push(3, 'c');
+/// assert_eq!(heap.pop(), Some((3, 'c')));
+/// assert_eq!(heap.pop(), Some((5, 'a')));
+/// assert_eq!(heap.pop(), None);
+/// ```
+///
+/// # References
+///
+/// * [Ahuja, R. K., Mehlhorn, K., Orlin, J., & Tarjan, R. E. (1990). Faster algorithms for the shortest path problem. Journal of the ACM, 37(2), 213–223.][1]
+///
+/// [1]: https://doi.org/10.1145/77600.77615
+#[derive(Clone, Debug)]
+pub struct RadixHeap<K, V> {
+  // `buckets[i]` contains elements whose keys differ from `last` first at the (i - 1)-th bit
+  buckets: Vec<Vec<(K, V)>>,
+  last: K,
+  len: usize,
+}
+
+impl<K, V> Default for RadixHeap<K, V>
+where
+  K: PrimUint,
+{
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K, V> RadixHeap<K, V>
+where
+  K: PrimUint,
+{
+  /// Creates an empty `RadixHeap`.
+  ///
+  /// # Time complexity
+  /// $O(w)$ where $w$ is the bit width of `K`
+  pub fn new() -> Self {
+    let bits = K::ZERO.count_zeros() as usize;
+    Self { buckets: (0..=bits).map(|_| vec![]).collect(), last: K::ZERO, len: 0 }
+  }
+
+  /// Returns the number of elements.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns `true` iff the heap contains no elements.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Pushes an element.
+  ///
+  /// # Panics
+  /// Panics if `key` is less than the key of the last popped element.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn push(&mut self, key: K, value: V) {
+    assert!(
+      key >= self.last,
+      "key is less than the last popped key: the key is {:?} but the last is {:?}",
+      key,
+      self.last
+    );
+    let i = self.bucket_index(key);
+    self.buckets[i].push((key, value));
+    self.len += 1;
+  }
+
+  /// Removes the element with the smallest key and returns it.
+  ///
+  /// # Time complexity
+  /// $O(w)$ amortized where $w$ is the bit width of `K`
+  pub fn pop(&mut self) -> Option<(K, V)> {
+    if self.buckets[0].is_empty() {
+      let i = self.buckets.iter().position(|bucket| !bucket.is_empty())?;
+      // TODO: Use `mem::take` since 1.40.0
+      let bucket = mem::replace(&mut self.buckets[i], vec![]);
+      self.last = bucket.iter().map(|&(key, _)| key).min().unwrap();
+      for (key, value) in bucket {
+        // Every element moves to a bucket with a smaller index.
+        let j = self.bucket_index(key);
+        debug_assert!(j < i);
+        self.buckets[j].push((key, value));
+      }
+    }
+    self.len -= 1;
+    self.buckets[0].pop()
+  }
+
+  fn bucket_index(&self, key: K) -> usize {
+    // the bit length of `key ^ self.last`
+    self.buckets.len() - 1 - (key ^ self.last).leading_zeros() as usize
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck_macros::quickcheck;
+  use std::cmp::Reverse;
+  use std::collections::BinaryHeap;
+
+  #[quickcheck]
+  fn prop(ops: Vec<Option<(u8, u8)>>) {
+    let mut heap = RadixHeap::new();
+    let mut naive = BinaryHeap::new();
+    let mut last = 0;
+
+    for op in ops {
+      match op {
+        Some((diff, value)) => {
+          let key = last + u32::from(diff);
+          heap.push(key, value);
+          naive.push(Reverse((key, value)));
+        }
+        None => match heap.pop() {
+          Some((key, value)) => {
+            let Reverse((naive_key, _)) = *naive.peek().unwrap();
+            assert_eq!(key, naive_key);
+            let mut rest = naive.into_vec();
+            let pos = rest.iter().position(|&Reverse(e)| e == (key, value)).unwrap();
+            rest.swap_remove(pos);
+            naive = rest.into();
+            last = key;
+          }
+          None => assert!(naive.is_empty()),
+        },
+      }
+      assert_eq!(heap.len(), naive.len());
+    }
+  }
+
+  #[test]
+  fn max_key() {
+    let mut heap = RadixHeap::new();
+    heap.push(u64::max_value(), ());
+    heap.push(0, ());
+    assert_eq!(heap.pop(), Some((0, ())));
+    assert_eq!(heap.pop(), Some((u64::max_value(), ())));
+    assert_eq!(heap.pop(), None);
+  }
+
+  #[test]
+  #[should_panic]
+  fn non_monotone() {
+    let mut heap = RadixHeap::new();
+    heap.push(3u32, ());
+    heap.pop();
+    heap.push(2, ());
+  }
+}
@@ -0,0 +1,333 @@
+//! Slope trick.
+
+use crate::num::primitive::SignedInt as PrimSint;
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A convex piecewise linear function $f \colon \mathbb{Z} \to \mathbb{Z}$, manipulated by
+/// the technique called slope trick.
+///
+/// $f$ is represented by its minimum value and the multiset of its breakpoints, where the slope
+/// increases by $1$ at each breakpoint. The breakpoints on the left side and the right side of
+/// the minimum are kept in two heaps with lazily added offsets.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::collections::SlopeTrick;
+/// // f(x) = |x - 1| + |x - 5| + max(0, 3 - x)
+/// let mut f = SlopeTrick::new();
+/// f.add_abs(1);
+/// f.add_abs(5);
+/// f.add_a_minus_x(3);
+///
+/// assert_eq!(f.min(), 4);
+/// assert_eq!(f.argmin(), (Some(3), Some(5)));
+/// assert_eq!(f.eval(0), 9);
+///
+/// // f(x) := min { f(y) | y <= x }
+/// f.prefix_min();
+/// assert_eq!(f.eval(10), 4);
+/// ```
+///
+/// # References
+///
+/// * [slope trick (1) 解説編 - うしさんのお部屋][1]
+///
+/// [1]: https://maspypy.com/slope-trick-1-%E8%A7%A3%E8%AA%AC%E7%B7%A8
+#[derive(Clone, Debug)]
+pub struct SlopeTrick<Int> {
+  min: Int,
+  // breakpoints on the left side of the minimum
+  left: BinaryHeap<Int>,
+  left_offset: Int,
+  // breakpoints on the right side of the minimum
+  right: BinaryHeap<Reverse<Int>>,
+  right_offset: Int,
+}
+
+impl<Int> Default for SlopeTrick<Int>
+where
+  Int: PrimSint,
+{
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<Int> SlopeTrick<Int>
+where
+  Int: PrimSint,
+{
+  /// Creates a new `SlopeTrick` representing $f(x) = 0$.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn new() -> Self {
+    Self {
+      min: Int::ZERO,
+      left: BinaryHeap::new(),
+      left_offset: Int::ZERO,
+      right: BinaryHeap::new(),
+      right_offset: Int::ZERO,
+    }
+  }
+
+  /// Returns $\min_x f(x)$.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn min(&self) -> Int {
+    self.min
+  }
+
+  /// Returns the closed interval of $x$ that minimize $f(x)$.
+  ///
+  /// `None` represents that the interval is unbounded in that direction.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn argmin(&self) -> (Option<Int>, Option<Int>) {
+    (self.left_top(), self.right_top())
+  }
+
+  /// Returns $f(x)$.
+  ///
+  /// # Time complexity
+  /// $O(n)$ where $n$ is the number of breakpoints
+  pub fn eval(&self, x: Int) -> Int {
+    let l = self.left.iter().map(|&l| l + self.left_offset).filter(|&l| l > x).map(|l| l - x);
+    let r = self.right.iter().map(|&Reverse(r)| r + self.right_offset).filter(|&r| x > r);
+    let r = r.map(|r| x - r);
+    self.min + l.sum::<Int>() + r.sum::<Int>()
+  }
+
+  /// Adds a constant $a$ to $f(x)$.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn add_const(&mut self, a: Int) {
+    self.min += a;
+  }
+
+  /// Adds $\max(0, x - a)$ to $f(x)$.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$ where $n$ is the number of breakpoints
+  pub fn add_x_minus_a(&mut self, a: Int) {
+    if let Some(l) = self.left_top() {
+      if l > a {
+        self.min += l - a;
+      }
+    }
+    self.push_left(a);
+    let l = self.pop_left();
+    self.push_right(l);
+  }
+
+  /// Adds $\max(0, a - x)$ to $f(x)$.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$ where $n$ is the number of breakpoints
+  pub fn add_a_minus_x(&mut self, a: Int) {
+    if let Some(r) = self.right_top() {
+      if a > r {
+        self.min += a - r;
+      }
+    }
+    self.push_right(a);
+    let r = self.pop_right();
+    self.push_left(r);
+  }
+
+  /// Adds $|x - a|$ to $f(x)$.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$ where $n$ is the number of breakpoints
+  pub fn add_abs(&mut self, a: Int) {
+    self.add_x_minus_a(a);
+    self.add_a_minus_x(a);
+  }
+
+  /// Replaces $f(x)$ with $\min_{y \le x} f(y)$.
+  ///
+  /// # Time complexity
+  /// $O(n)$ where $n$ is the number of breakpoints
+  pub fn prefix_min(&mut self) {
+    self.right.clear();
+  }
+
+  /// Replaces $f(x)$ with $\min_{y \ge x} f(y)$.
+  ///
+  /// # Time complexity
+  /// $O(n)$ where $n$ is the number of breakpoints
+  pub fn suffix_min(&mut self) {
+    self.left.clear();
+  }
+
+  /// Replaces $f(x)$ with $\min_{x - b \le y \le x - a} f(y)$.
+  ///
+  /// # Panics
+  /// Panics if `a > b`.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn sliding_window_min(&mut self, a: Int, b: Int) {
+    assert!(a <= b, "invalid window: a = {:?}, b = {:?}", a, b);
+    self.left_offset += a;
+    self.right_offset += b;
+  }
+
+  /// Replaces $f(x)$ with $f(x - a)$.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn translate(&mut self, a: Int) {
+    self.sliding_window_min(a, a);
+  }
+
+  fn left_top(&self) -> Option<Int> {
+    self.left.peek().map(|&l| l + self.left_offset)
+  }
+
+  fn right_top(&self) -> Option<Int> {
+    self.right.peek().map(|&Reverse(r)| r + self.right_offset)
+  }
+
+  fn push_left(&mut self, l: Int) {
+    self.left.push(l - self.left_offset);
+  }
+
+  fn push_right(&mut self, r: Int) {
+    self.right.push(Reverse(r - self.right_offset));
+  }
+
+  fn pop_left(&mut self) -> Int {
+    self.left.pop().unwrap() + self.left_offset
+  }
+
+  fn pop_right(&mut self) -> Int {
+    self.right.pop().unwrap().0 + self.right_offset
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck::{Arbitrary, Gen};
+  use quickcheck_macros::quickcheck;
+  use rand::Rng as _;
+  use std::cmp;
+
+  // Values of a function on [-W, W], extrapolated linearly outside of it.
+  struct Naive(Vec<i64>);
+
+  const W: i64 = 2000;
+
+  impl Naive {
+    fn get(&self, x: i64) -> i64 {
+      let v = &self.0;
+      if x < -W {
+        v[0] + (v[0] - v[1]) * (-W - x)
+      } else if x > W {
+        v[v.len() - 1] + (v[v.len() - 1] - v[v.len() - 2]) * (x - W)
+      } else {
+        v[(x + W) as usize]
+      }
+    }
+
+    fn map(&mut self, f: impl Fn(&Self, i64) -> i64) {
+      self.0 = (-W..=W).map(|x| f(self, x)).collect();
+    }
+  }
+
+  #[derive(Clone, Debug)]
+  enum Op {
+    AddConst(i64),
+    AddXMinusA(i64),
+    AddAMinusX(i64),
+    AddAbs(i64),
+    PrefixMin,
+    SuffixMin,
+    SlidingWindowMin(i64, i64),
+    Translate(i64),
+  }
+
+  impl Arbitrary for Op {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      let a = g.gen_range(-20, 21);
+      match g.gen_range(0, 8) {
+        0 => Op::AddConst(a),
+        1 => Op::AddXMinusA(a),
+        2 => Op::AddAMinusX(a),
+        3 => Op::AddAbs(a),
+        4 => Op::PrefixMin,
+        5 => Op::SuffixMin,
+        6 => Op::SlidingWindowMin(a, a + g.gen_range(0, 20)),
+        _ => Op::Translate(a),
+      }
+    }
+  }
+
+  #[quickcheck]
+  fn prop(ops: Vec<Op>) {
+    let mut f = SlopeTrick::new();
+    let mut naive = Naive(vec![0; (2 * W + 1) as usize]);
+
+    for op in ops.into_iter().take(40) {
+      match op {
+        Op::AddConst(a) => {
+          f.add_const(a);
+          naive.map(|g, x| g.get(x) + a);
+        }
+        Op::AddXMinusA(a) => {
+          f.add_x_minus_a(a);
+          naive.map(|g, x| g.get(x) + cmp::max(0, x - a));
+        }
+        Op::AddAMinusX(a) => {
+          f.add_a_minus_x(a);
+          naive.map(|g, x| g.get(x) + cmp::max(0, a - x));
+        }
+        Op::AddAbs(a) => {
+          f.add_abs(a);
+          naive.map(|g, x| g.get(x) + (x - a).abs());
+        }
+        Op::PrefixMin => {
+          f.prefix_min();
+          naive.0.iter_mut().fold(i64::max_value(), |acc, y| {
+            *y = cmp::min(acc, *y);
+            *y
+          });
+        }
+        Op::SuffixMin => {
+          f.suffix_min();
+          naive.0.iter_mut().rev().fold(i64::max_value(), |acc, y| {
+            *y = cmp::min(acc, *y);
+            *y
+          });
+        }
+        Op::SlidingWindowMin(a, b) => {
+          f.sliding_window_min(a, b);
+          naive.map(|g, x| (x - b..=x - a).map(|y| g.get(y)).min().unwrap());
+        }
+        Op::Translate(a) => {
+          f.translate(a);
+          naive.map(|g, x| g.get(x - a));
+        }
+      }
+
+      let min = *naive.0.iter().min().unwrap();
+      assert_eq!(f.min(), min);
+
+      let (l, r) = f.argmin();
+      let mut argmin = (-W..=W).filter(|&x| naive.get(x) == min);
+      assert_eq!(l.unwrap_or(-W), argmin.clone().next().unwrap());
+      assert_eq!(r.unwrap_or(W), argmin.next_back().unwrap());
+
+      for x in (-W..=W).step_by(7) {
+        assert_eq!(f.eval(x), naive.get(x));
+      }
+    }
+  }
+}
@@ -8,14 +8,28 @@
 //! [1]: https://doi.org/10.1145/116873.116878
 //! [2]: https://en.wikipedia.org/w/index.php?title=Disjoint-set_data_structure&oldid=962428397
 
+pub use self::aggregate_quick_union::*;
+pub use self::disjoint_sets::*;
+pub use self::parity_quick_union::*;
+pub use self::partially_persistent_quick_union::*;
+pub use self::potentialized_quick_union::*;
 pub use self::quick_find::*;
 pub use self::quick_union::*;
+pub use self::undoable_quick_union::*;
 
+mod aggregate_quick_union;
+#[allow(clippy::module_inception)]
+mod disjoint_sets;
+mod parity_quick_union;
+mod partially_persistent_quick_union;
+mod potentialized_quick_union;
 mod quick_find;
 mod quick_union;
+mod undoable_quick_union;
 
 #[cfg(test)]
 mod tests {
+  use super::DisjointSets;
   use std::collections::HashSet;
 
   #[derive(Clone, PartialEq, Eq, Debug)]
@@ -49,4 +63,49 @@ mod tests {
       self.set(i).len()
     }
   }
+
+  // Checks the `DisjointSets` implementation of `D` against `NaiveDisjointSets`.
+  pub fn check_disjoint_sets<D, F>(new: F, len: usize, ops: &[(usize, usize)])
+  where
+    D: DisjointSets,
+    F: FnOnce(usize) -> D,
+  {
+    let len = len % 30;
+    let mut uf = new(len);
+    let mut naive =
+      NaiveDisjointSets((0..len).map(|i| Some(Some(i).into_iter().collect())).collect());
+    assert_eq!(uf.len(), len);
+
+    for &(i, j) in ops {
+      if len == 0 {
+        break;
+      }
+      let (i, j) = (i % len, j % len);
+      assert_eq!(uf.unite(i, j), naive.union(i, j));
+      assert_eq!(uf.sets_len(), naive.sets_len());
+
+      for i in 0..len {
+        let r = uf.find(i);
+        assert_eq!(uf.find(r), r);
+        assert!(naive.set(i).contains(&r));
+        assert_eq!(uf.set_len(i), naive.set_len(i));
+        assert_eq!(uf.belong_to_same_set(i, j), naive.find(i) == naive.find(j));
+      }
+    }
+
+    let groups = uf.groups();
+    assert_eq!(groups.len(), naive.sets_len());
+    for (k, group) in groups.iter().enumerate() {
+      assert!(group.windows(2).all(|w| w[0] < w[1]));
+      assert!(k == 0 || groups[k - 1][0] < group[0]);
+      assert_eq!(&group.iter().copied().collect::<HashSet<_>>(), naive.set(group[0]));
+    }
+
+    let reprs = uf.representatives().collect::<Vec<_>>();
+    assert_eq!(reprs.len(), naive.sets_len());
+    assert!(reprs.windows(2).all(|w| w[0] < w[1]));
+    for r in reprs {
+      assert_eq!(uf.find(r), r);
+    }
+  }
 }
@@ -0,0 +1,244 @@
+use crate::algebra::structures::{CommutativeSemigroup, Monoid};
+use crate::disjoint_sets::{DisjointSets, QuickUnion};
+use crate::utils::index_bounds_check::assert_index;
+
+use std::iter::FromIterator;
+
+/// A disjoint-set data structure based on the weighted quick-union algorithm, which maintains
+/// the sum of values in each set under a commutative semigroup.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::structures::{Max, Sum};
+/// # use k7lib::disjoint_sets::AggregateQuickUnion;
+/// // (number of edges, maximum weight)
+/// let mut uf = AggregateQuickUnion::<(Sum<u32>, Max<u32>)>::new(4);
+///
+/// for &(i, j, w) in &[(0, 1, 5), (1, 2, 3), (2, 0, 4)] {
+///   uf.unite(i, j);
+///   uf.set_append(i, &(Sum(1), Max(w)));
+/// }
+///
+/// assert_eq!(uf.set_sum(2), &(Sum(3), Max(5)));
+/// assert_eq!(uf.set_sum(3), &(Sum(0), Max(0)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AggregateQuickUnion<T> {
+  uf: QuickUnion,
+  // `sums[i]` is `Some` iff `i` is a root
+  sums: Vec<Option<T>>,
+}
+
+impl<M> AggregateQuickUnion<M>
+where
+  M: CommutativeSemigroup + Monoid,
+{
+  /// Creates a new `AggregateQuickUnion` with the given number of elements, where each
+  /// element has an identity element.
+  ///
+  /// Initially it consists of $n$ singletons:
+  /// $\lbrace \lbrace 0 \rbrace, \lbrace 1 \rbrace, \dots, \lbrace n - 1 \rbrace \rbrace$,
+  /// where $n$ = `len`.
+  ///
+  /// # Time complexity
+  /// $\Theta(n)$
+  pub fn new(len: usize) -> Self {
+    (0..len).map(|_| M::identity()).collect()
+  }
+}
+
+impl<T> AggregateQuickUnion<T>
+where
+  T: CommutativeSemigroup,
+{
+  /// Returns the total number of elements that belong to disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.uf.len()
+  }
+
+  /// Returns the number of disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn sets_len(&self) -> usize {
+    self.uf.sets_len()
+  }
+
+  /// Returns the representative of the set that the given element belongs to.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn find(&mut self, i: usize) -> usize {
+    self.uf.find(i)
+  }
+
+  /// Unites two disjoint sets that the given elements belong to into one.
+  ///
+  /// Returns `false` iff two elements already belong to the same set.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn unite(&mut self, i: usize, j: usize) -> bool {
+    let i = self.find(i);
+    let j = self.find(j);
+
+    if !self.uf.unite(i, j) {
+      return false;
+    }
+    let i_sum = self.sums[i].take().unwrap();
+    let j_sum = self.sums[j].take().unwrap();
+    let root = self.find(i);
+    self.sums[root] = Some(i_sum.op(&j_sum));
+    true
+  }
+
+  /// Returns `true` iff the given elements belong to the same set.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn belong_to_same_set(&mut self, i: usize, j: usize) -> bool {
+    self.uf.belong_to_same_set(i, j)
+  }
+
+  /// Returns the number of elements that belong to the same set as the given element.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn set_len(&mut self, i: usize) -> usize {
+    self.uf.set_len(i)
+  }
+
+  /// Returns the sum of values in the set that the given element belongs to.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn set_sum(&mut self, i: usize) -> &T {
+    assert_index(i, self.len());
+
+    let i = self.find(i);
+    self.sums[i].as_ref().unwrap()
+  }
+
+  /// Appends the given value to the sum of the set that the given element belongs to with
+  /// a semigroup's binary operation.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn set_append(&mut self, i: usize, value: &T) {
+    assert_index(i, self.len());
+
+    let i = self.find(i);
+    self.sums[i].as_mut().unwrap().op_assign_right(value);
+  }
+}
+
+impl<T> From<Vec<T>> for AggregateQuickUnion<T>
+where
+  T: CommutativeSemigroup,
+{
+  /// Creates a new `AggregateQuickUnion` of singletons from the values of each element.
+  ///
+  /// # Time complexity
+  /// $\Theta(n)$
+  fn from(vec: Vec<T>) -> Self {
+    Self { uf: QuickUnion::new(vec.len()), sums: vec.into_iter().map(Some).collect() }
+  }
+}
+
+impl<T> FromIterator<T> for AggregateQuickUnion<T>
+where
+  T: CommutativeSemigroup,
+{
+  /// Creates a new `AggregateQuickUnion` of singletons from the values of each element.
+  ///
+  /// # Time complexity
+  /// $\Theta(n)$
+  fn from_iter<I>(iter: I) -> Self
+  where
+    I: IntoIterator<Item = T>,
+  {
+    Self::from(iter.into_iter().collect::<Vec<_>>())
+  }
+}
+
+impl<T> DisjointSets for AggregateQuickUnion<T>
+where
+  T: CommutativeSemigroup,
+{
+  fn len(&self) -> usize {
+    AggregateQuickUnion::len(self)
+  }
+
+  fn sets_len(&self) -> usize {
+    AggregateQuickUnion::sets_len(self)
+  }
+
+  fn find(&mut self, i: usize) -> usize {
+    AggregateQuickUnion::find(self, i)
+  }
+
+  fn unite(&mut self, i: usize, j: usize) -> bool {
+    AggregateQuickUnion::unite(self, i, j)
+  }
+
+  fn set_len(&mut self, i: usize) -> usize {
+    AggregateQuickUnion::set_len(self, i)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::algebra::structures::Sum;
+  use crate::disjoint_sets::tests::{check_disjoint_sets, NaiveDisjointSets};
+  use quickcheck_macros::quickcheck;
+
+  #[quickcheck]
+  fn prop(values: Vec<i32>, ops: Vec<(usize, usize, Option<i32>)>) {
+    let mut values = values.into_iter().map(i64::from).collect::<Vec<_>>();
+    let mut uf = values.iter().copied().map(Sum).collect::<AggregateQuickUnion<_>>();
+    let mut naive =
+      NaiveDisjointSets((0..values.len()).map(|i| Some(Some(i).into_iter().collect())).collect());
+
+    for (i, j, value) in ops {
+      if values.is_empty() {
+        break;
+      }
+      let i = i % values.len();
+      let j = j % values.len();
+
+      match value {
+        Some(value) => {
+          uf.set_append(i, &Sum(i64::from(value)));
+          values[i] += i64::from(value);
+        }
+        None => assert_eq!(uf.unite(i, j), naive.union(i, j)),
+      }
+
+      assert_eq!(uf.sets_len(), naive.sets_len());
+      for &k in &[i, j] {
+        assert_eq!(uf.set_len(k), naive.set_len(k));
+        let sum = naive.set(k).iter().map(|&l| values[l]).sum::<i64>();
+        assert_eq!(uf.set_sum(k), &Sum(sum));
+      }
+    }
+  }
+
+  #[test]
+  fn new_test() {
+    let mut uf = AggregateQuickUnion::<Sum<i32>>::new(3);
+    assert_eq!(uf.len(), 3);
+    for i in 0..3 {
+      assert_eq!(uf.set_sum(i), &Sum(0));
+    }
+  }
+
+  #[quickcheck]
+  fn disjoint_sets_prop(len: usize, ops: Vec<(usize, usize)>) {
+    check_disjoint_sets(AggregateQuickUnion::<Sum<i32>>::new, len, &ops);
+  }
+}
@@ -0,0 +1,108 @@
+use std::iter::FusedIterator;
+
+/// A common interface of disjoint-set data structures over the elements $0, 1, \dots, n - 1$.
+///
+/// Methods take `&mut self` even for queries, so that implementations can compress paths.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::disjoint_sets::{DisjointSets, QuickFind, QuickUnion};
+/// fn partition<D: DisjointSets>(mut uf: D) -> Vec<Vec<usize>> {
+///   uf.unite(0, 2);
+///   uf.unite(3, 4);
+///   uf.unite(2, 4);
+///   uf.groups()
+/// }
+///
+/// assert_eq!(partition(QuickFind::new(6)), [vec![0, 2, 3, 4], vec![1], vec![5]]);
+/// assert_eq!(partition(QuickUnion::new(6)), [vec![0, 2, 3, 4], vec![1], vec![5]]);
+/// ```
+pub trait DisjointSets {
+  /// Returns the total number of elements that belong to disjoint sets.
+  fn len(&self) -> usize;
+
+  /// Returns `true` iff there are no elements.
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns the number of disjoint sets.
+  fn sets_len(&self) -> usize;
+
+  /// Returns the representative of the set that the given element belongs to.
+  fn find(&mut self, i: usize) -> usize;
+
+  /// Unites two disjoint sets that the given elements belong to into one.
+  ///
+  /// Returns `false` iff two elements already belong to the same set.
+  fn unite(&mut self, i: usize, j: usize) -> bool;
+
+  /// Returns `true` iff the given elements belong to the same set.
+  fn belong_to_same_set(&mut self, i: usize, j: usize) -> bool {
+    self.find(i) == self.find(j)
+  }
+
+  /// Returns the number of elements that belong to the same set as the given element.
+  fn set_len(&mut self, i: usize) -> usize;
+
+  /// Returns the whole partition.
+  ///
+  /// Each set is sorted in ascending order, and the sets are sorted by their smallest elements.
+  ///
+  /// # Time complexity
+  /// $O(n)$ calls of [`find`](Self::find)
+  fn groups(&mut self) -> Vec<Vec<usize>> {
+    // `indices[r]` is the index in `groups` of the set whose representative is `r`
+    let mut indices = vec![usize::max_value(); self.len()];
+    let mut groups = Vec::<Vec<_>>::with_capacity(self.sets_len());
+    for i in 0..self.len() {
+      let r = self.find(i);
+      if indices[r] == usize::max_value() {
+        indices[r] = groups.len();
+        groups.push(Vec::with_capacity(self.set_len(r)));
+      }
+      groups[indices[r]].push(i);
+    }
+    groups
+  }
+
+  /// Returns an iterator over the representatives of all sets in ascending order.
+  ///
+  /// # Time complexity
+  /// $O(n)$ calls of [`find`](Self::find) in total
+  fn representatives(&mut self) -> Representatives<'_, Self> {
+    Representatives { sets: self, next: 0 }
+  }
+}
+
+/// An iterator created by [`DisjointSets::representatives`].
+#[derive(Debug)]
+pub struct Representatives<'a, D: ?Sized> {
+  sets: &'a mut D,
+  next: usize,
+}
+
+impl<D> Iterator for Representatives<'_, D>
+where
+  D: DisjointSets + ?Sized,
+{
+  type Item = usize;
+
+  fn next(&mut self) -> Option<usize> {
+    while self.next < self.sets.len() {
+      let i = self.next;
+      self.next += 1;
+      if self.sets.find(i) == i {
+        return Some(i);
+      }
+    }
+    None
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, Some(self.sets.len() - self.next))
+  }
+}
+
+impl<D> FusedIterator for Representatives<'_, D> where D: DisjointSets + ?Sized {}
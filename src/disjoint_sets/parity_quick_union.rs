@@ -0,0 +1,319 @@
+use crate::disjoint_sets::ContradictionError;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::mem;
+
+/// A disjoint-set data structure based on the weighted quick-union algorithm, where each element
+/// has one of two colors, under the constraints that two elements have the same color or
+/// different colors.
+///
+/// This is useful to check whether a graph is bipartite incrementally: uniting the endpoints of
+/// each edge with [`unite_different`](Self::unite_different) fails iff the edge makes an odd
+/// cycle.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::disjoint_sets::ParityQuickUnion;
+/// let mut uf = ParityQuickUnion::new(4);
+///
+/// assert_eq!(uf.unite_different(0, 1), Ok(true));
+/// assert_eq!(uf.unite_different(1, 2), Ok(true));
+/// assert_eq!(uf.have_same_color(0, 2), Some(true));
+/// assert_eq!(uf.have_same_color(0, 3), None);
+///
+/// // an odd cycle
+/// assert!(uf.unite_different(2, 0).is_err());
+/// assert_eq!(uf.first_contradiction(), Some(2));
+///
+/// let colors = uf.coloring();
+/// assert_ne!(colors[0], colors[1]);
+/// assert_eq!(colors[0], colors[2]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParityQuickUnion {
+  nodes: Vec<Node>,
+  sets_len: usize,
+  constraints_len: usize,
+  first_contradiction: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+  parent: usize,
+  len: usize,
+  // `true` iff the color of the node differs from the one of its parent
+  parity: bool,
+}
+
+impl ParityQuickUnion {
+  fn is_root(&self, i: usize) -> bool {
+    self.nodes[i].parent == i
+  }
+
+  /// Creates a new `ParityQuickUnion` with the given number of elements.
+  ///
+  /// Initially it consists of $n$ singletons:
+  /// $\lbrace \lbrace 0 \rbrace, \lbrace 1 \rbrace, \dots, \lbrace n - 1 \rbrace \rbrace$,
+  /// where $n$ = `len`.
+  ///
+  /// # Time complexity
+  /// $\Theta(n)$
+  pub fn new(len: usize) -> Self {
+    Self {
+      // Initially all nodes are root.
+      nodes: (0..len).map(|i| Node { parent: i, len: 1, parity: false }).collect(),
+      sets_len: len,
+      constraints_len: 0,
+      first_contradiction: None,
+    }
+  }
+
+  /// Returns the total number of elements that belong to disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Returns the number of disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn sets_len(&self) -> usize {
+    self.sets_len
+  }
+
+  /// Returns the representative of the set that the given element belongs to.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn find(&mut self, i: usize) -> usize {
+    assert_index(i, self.len());
+
+    if self.is_root(i) {
+      return i;
+    }
+    // Path compression
+    // The recursion is shallow since the height of trees is O(log n) by union by size.
+    let parent = self.nodes[i].parent;
+    let root = self.find(parent);
+    if parent != root {
+      self.nodes[i].parity ^= self.nodes[parent].parity;
+      self.nodes[i].parent = root;
+    }
+    root
+  }
+
+  /// Returns `true` iff the color of the given element differs from the one of the
+  /// representative of its set.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn parity(&mut self, i: usize) -> bool {
+    assert_index(i, self.len());
+
+    let root = self.find(i);
+    i != root && self.nodes[i].parity
+  }
+
+  /// Returns whether the given elements have the same color, if they belong to the same set.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn have_same_color(&mut self, i: usize, j: usize) -> Option<bool> {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    if self.find(i) != self.find(j) {
+      return None;
+    }
+    Some(self.parity(i) == self.parity(j))
+  }
+
+  /// Returns `true` iff the given elements belong to the same set.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn belong_to_same_set(&mut self, i: usize, j: usize) -> bool {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    self.find(i) == self.find(j)
+  }
+
+  /// Returns the number of elements that belong to the same set as the given element.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn set_len(&mut self, i: usize) -> usize {
+    assert_index(i, self.len());
+
+    let i = self.find(i);
+    self.nodes[i].len
+  }
+
+  /// Unites two disjoint sets that the given elements belong to into one, with the constraint
+  /// that they have the same color.
+  ///
+  /// Returns `Ok(false)` iff two elements already belong to the same set and the constraint is
+  /// consistent with the existing ones.
+  ///
+  /// # Errors
+  /// Returns an error iff two elements already belong to the same set and have different
+  /// colors. In this case nothing is changed except that the contradiction is recorded.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn unite_same(&mut self, i: usize, j: usize) -> Result<bool, ContradictionError> {
+    self.unite(i, j, false)
+  }
+
+  /// Unites two disjoint sets that the given elements belong to into one, with the constraint
+  /// that they have different colors.
+  ///
+  /// Returns `Ok(false)` iff two elements already belong to the same set and the constraint is
+  /// consistent with the existing ones.
+  ///
+  /// # Errors
+  /// Returns an error iff two elements already belong to the same set and have the same color,
+  /// that is, the constraint makes an odd cycle. In this case nothing is changed except that
+  /// the contradiction is recorded.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn unite_different(&mut self, i: usize, j: usize) -> Result<bool, ContradictionError> {
+    self.unite(i, j, true)
+  }
+
+  fn unite(&mut self, i: usize, j: usize, parity: bool) -> Result<bool, ContradictionError> {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    let index = self.constraints_len;
+    self.constraints_len += 1;
+
+    let mut ri = self.find(i);
+    let mut rj = self.find(j);
+
+    if ri == rj {
+      return if self.parity(i) ^ self.parity(j) == parity {
+        Ok(false)
+      } else {
+        self.first_contradiction.get_or_insert(index);
+        Err(ContradictionError::new())
+      };
+    }
+
+    let parity = self.parity(i) ^ self.parity(j) ^ parity;
+    // Union by size
+    if self.nodes[ri].len < self.nodes[rj].len {
+      mem::swap(&mut ri, &mut rj);
+    }
+
+    debug_assert!(self.is_root(ri));
+    debug_assert!(self.is_root(rj));
+    debug_assert!(self.nodes[ri].len >= self.nodes[rj].len);
+
+    self.nodes[rj].parent = ri;
+    self.nodes[rj].parity = parity;
+    self.nodes[ri].len += self.nodes[rj].len;
+    self.sets_len -= 1;
+    Ok(true)
+  }
+
+  /// Returns `true` iff no constraint has contradicted the existing ones so far.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn is_consistent(&self) -> bool {
+    self.first_contradiction.is_none()
+  }
+
+  /// Returns the 0-based index of the first constraint that contradicted the existing ones,
+  /// counting all calls of [`unite_same`](Self::unite_same) and
+  /// [`unite_different`](Self::unite_different).
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn first_contradiction(&self) -> Option<usize> {
+    self.first_contradiction
+  }
+
+  /// Returns a coloring of all elements which satisfies all the constraints accepted so far,
+  /// where the representative of each set is colored `false`.
+  ///
+  /// # Time complexity
+  /// $O(n \alpha(n))$ amortized
+  pub fn coloring(&mut self) -> Vec<bool> {
+    (0..self.len()).map(|i| self.parity(i)).collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::disjoint_sets::tests::NaiveDisjointSets;
+  use quickcheck_macros::quickcheck;
+
+  #[quickcheck]
+  fn prop(len: usize, ops: Vec<(usize, usize, bool)>) {
+    let len = len % 20 + 1;
+    let mut uf = ParityQuickUnion::new(len);
+    let mut naive =
+      NaiveDisjointSets((0..len).map(|i| Some(Some(i).into_iter().collect())).collect());
+    // the accepted constraints
+    let mut constraints = vec![];
+    let mut first_contradiction = None;
+
+    for (index, (i, j, different)) in ops.into_iter().enumerate() {
+      let (i, j) = (i % len, j % len);
+      let same = naive.find(i) == naive.find(j);
+      let colors = uf.coloring();
+      let result = if different { uf.unite_different(i, j) } else { uf.unite_same(i, j) };
+
+      if same && (colors[i] != colors[j]) != different {
+        assert_eq!(result, Err(ContradictionError::new()));
+        first_contradiction.get_or_insert(index);
+      } else {
+        assert_eq!(result, Ok(!same));
+        naive.union(i, j);
+        constraints.push((i, j, different));
+      }
+      assert_eq!(uf.is_consistent(), first_contradiction.is_none());
+      assert_eq!(uf.first_contradiction(), first_contradiction);
+
+      assert_eq!(uf.sets_len(), naive.sets_len());
+      let colors = uf.coloring();
+      for &(i, j, different) in &constraints {
+        assert_eq!(colors[i] != colors[j], different);
+      }
+      for i in 0..len {
+        assert_eq!(uf.set_len(i), naive.set_len(i));
+        assert!(!colors[uf.find(i)]);
+        for j in 0..len {
+          let same = naive.find(i) == naive.find(j);
+          assert_eq!(uf.belong_to_same_set(i, j), same);
+          assert_eq!(
+            uf.have_same_color(i, j),
+            if same { Some(colors[i] == colors[j]) } else { None }
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn odd_cycle() {
+    let mut uf = ParityQuickUnion::new(5);
+    for i in 0..4 {
+      assert_eq!(uf.unite_different(i, i + 1), Ok(true));
+    }
+    assert!(uf.is_consistent());
+    assert_eq!(uf.unite_different(4, 0), Err(ContradictionError::new()));
+    assert_eq!(uf.unite_same(4, 0), Ok(false));
+    assert_eq!(uf.unite_different(0, 0), Err(ContradictionError::new()));
+    assert_eq!(uf.first_contradiction(), Some(4));
+  }
+}
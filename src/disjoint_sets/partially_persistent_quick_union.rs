@@ -0,0 +1,253 @@
+use crate::disjoint_sets::DisjointSets;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::mem;
+
+/// A partially persistent disjoint-set data structure based on the weighted quick-union
+/// algorithm without path compression, which records when each union happened.
+///
+/// The time is the number of calls of [`unite`](Self::unite) so far, and the state at time $t$
+/// is the one just after the first $t$ calls. Any past state can be queried, while only the
+/// latest state can be modified.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::disjoint_sets::PartiallyPersistentQuickUnion;
+/// let mut uf = PartiallyPersistentQuickUnion::new(4);
+/// uf.unite(0, 1);
+/// uf.unite(2, 3);
+/// uf.unite(1, 2);
+/// assert_eq!(uf.now(), 3);
+///
+/// assert!(!uf.belong_to_same_set_at(0, 3, 2));
+/// assert!(uf.belong_to_same_set_at(0, 3, 3));
+/// assert_eq!(uf.connected_time(0, 1), Some(1));
+/// assert_eq!(uf.connected_time(0, 3), Some(3));
+/// assert_eq!(uf.set_len_at(0, 2), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PartiallyPersistentQuickUnion {
+  nodes: Vec<Node>,
+  sets_len: usize,
+  now: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+  parent: usize,
+  // the time since when the node has not been a root, `usize::max_value()` if it is a root
+  united_at: usize,
+  // the pairs of a time and the size of the set since the time, while the node is a root
+  lens: Vec<(usize, usize)>,
+}
+
+impl PartiallyPersistentQuickUnion {
+  fn is_root_at(&self, i: usize, t: usize) -> bool {
+    self.nodes[i].united_at > t
+  }
+
+  /// Creates a new `PartiallyPersistentQuickUnion` with the given number of elements.
+  ///
+  /// Initially it consists of $n$ singletons:
+  /// $\lbrace \lbrace 0 \rbrace, \lbrace 1 \rbrace, \dots, \lbrace n - 1 \rbrace \rbrace$,
+  /// where $n$ = `len`.
+  ///
+  /// # Time complexity
+  /// $\Theta(n)$
+  pub fn new(len: usize) -> Self {
+    Self {
+      // Initially all nodes are root.
+      nodes: (0..len)
+        .map(|i| Node { parent: i, united_at: usize::max_value(), lens: vec![(0, 1)] })
+        .collect(),
+      sets_len: len,
+      now: 0,
+    }
+  }
+
+  /// Returns the total number of elements that belong to disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Returns the current time, that is, the number of calls of [`unite`](Self::unite) so far.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn now(&self) -> usize {
+    self.now
+  }
+
+  /// Returns the current number of disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn sets_len(&self) -> usize {
+    self.sets_len
+  }
+
+  /// Returns the representative of the set that the given element belongs to at time `t`.
+  ///
+  /// A time later than the current one is regarded as the current one.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn find_at(&self, mut i: usize, t: usize) -> usize {
+    assert_index(i, self.len());
+
+    while !self.is_root_at(i, t) {
+      i = self.nodes[i].parent;
+    }
+    i
+  }
+
+  /// Unites two disjoint sets that the given elements belong to into one, and advances the time
+  /// by one.
+  ///
+  /// Returns `false` iff two elements already belong to the same set.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn unite(&mut self, i: usize, j: usize) -> bool {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    let mut i = self.find_at(i, self.now);
+    let mut j = self.find_at(j, self.now);
+    self.now += 1;
+
+    if i == j {
+      return false;
+    }
+    let i_len = self.nodes[i].lens.last().unwrap().1;
+    let j_len = self.nodes[j].lens.last().unwrap().1;
+    // Union by size
+    if i_len < j_len {
+      mem::swap(&mut i, &mut j);
+    }
+
+    self.nodes[j].parent = i;
+    self.nodes[j].united_at = self.now;
+    self.nodes[i].lens.push((self.now, i_len + j_len));
+    self.sets_len -= 1;
+    true
+  }
+
+  /// Returns `true` iff the given elements belong to the same set at time `t`.
+  ///
+  /// A time later than the current one is regarded as the current one.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn belong_to_same_set_at(&self, i: usize, j: usize, t: usize) -> bool {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    self.find_at(i, t) == self.find_at(j, t)
+  }
+
+  /// Returns the earliest time when the given elements belong to the same set, or `None` if
+  /// they do not belong to the same set even now.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn connected_time(&self, mut i: usize, mut j: usize) -> Option<usize> {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    if !self.belong_to_same_set_at(i, j, self.now) {
+      return None;
+    }
+    // The times are increasing along the path to the root, so climbing from the earlier one
+    // reaches the lowest common ancestor, and the last climbing time is the answer.
+    let mut t = 0;
+    while i != j {
+      let k = if self.nodes[i].united_at < self.nodes[j].united_at { &mut i } else { &mut j };
+      t = self.nodes[*k].united_at;
+      *k = self.nodes[*k].parent;
+    }
+    Some(t)
+  }
+
+  /// Returns the number of elements that belong to the same set as the given element at time
+  /// `t`.
+  ///
+  /// A time later than the current one is regarded as the current one.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn set_len_at(&self, i: usize, t: usize) -> usize {
+    assert_index(i, self.len());
+
+    let lens = &self.nodes[self.find_at(i, t)].lens;
+    let k = match lens.binary_search_by_key(&t, |&(s, _)| s) {
+      Ok(k) => k,
+      Err(k) => k - 1,
+    };
+    lens[k].1
+  }
+}
+
+impl DisjointSets for PartiallyPersistentQuickUnion {
+  fn len(&self) -> usize {
+    PartiallyPersistentQuickUnion::len(self)
+  }
+
+  fn sets_len(&self) -> usize {
+    PartiallyPersistentQuickUnion::sets_len(self)
+  }
+
+  fn find(&mut self, i: usize) -> usize {
+    self.find_at(i, self.now)
+  }
+
+  fn unite(&mut self, i: usize, j: usize) -> bool {
+    PartiallyPersistentQuickUnion::unite(self, i, j)
+  }
+
+  fn set_len(&mut self, i: usize) -> usize {
+    self.set_len_at(i, self.now)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::disjoint_sets::tests::{check_disjoint_sets, NaiveDisjointSets};
+  use quickcheck_macros::quickcheck;
+
+  #[quickcheck]
+  fn prop(len: usize, ops: Vec<(usize, usize)>, queries: Vec<(usize, usize, usize)>) {
+    let len = len % 30 + 1;
+    let mut uf = PartiallyPersistentQuickUnion::new(len);
+    let mut naive =
+      NaiveDisjointSets((0..len).map(|i| Some(Some(i).into_iter().collect())).collect());
+    // the states at each time
+    let mut history = vec![naive.clone()];
+
+    for (i, j) in ops {
+      let (i, j) = (i % len, j % len);
+      assert_eq!(uf.unite(i, j), naive.union(i, j));
+      assert_eq!(uf.sets_len(), naive.sets_len());
+      history.push(naive.clone());
+    }
+    assert_eq!(uf.now(), history.len() - 1);
+
+    for (i, j, t) in queries {
+      let (i, j, t) = (i % len, j % len, t % (history.len() + 2));
+      let ds = &history[t.min(uf.now())];
+      assert_eq!(uf.belong_to_same_set_at(i, j, t), ds.find(i) == ds.find(j));
+      assert_eq!(uf.set_len_at(i, t), ds.set_len(i));
+      assert_eq!(uf.connected_time(i, j), history.iter().position(|ds| ds.find(i) == ds.find(j)));
+    }
+  }
+
+  #[quickcheck]
+  fn disjoint_sets_prop(len: usize, ops: Vec<(usize, usize)>) {
+    check_disjoint_sets(PartiallyPersistentQuickUnion::new, len, &ops);
+  }
+}
@@ -0,0 +1,354 @@
+use crate::algebra::structures::Group;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::mem;
+
+/// A disjoint-set data structure based on the weighted quick-union algorithm, where each element
+/// has a potential in a group.
+///
+/// The group is not necessarily commutative. Potentials are determined only up to
+/// left multiplication within each set, so only differences between potentials in the same set
+/// are observable.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::algebra::structures::Sum;
+/// # use k7lib::disjoint_sets::PotentializedQuickUnion;
+/// let mut uf = PotentializedQuickUnion::new(4);
+///
+/// // pot(1) = pot(0) + 3, pot(2) = pot(1) + 4
+/// assert_eq!(uf.unite(0, 1, &Sum(3)), Ok(true));
+/// assert_eq!(uf.unite(1, 2, &Sum(4)), Ok(true));
+///
+/// assert_eq!(uf.diff(0, 2), Some(Sum(7)));
+/// assert_eq!(uf.diff(2, 0), Some(Sum(-7)));
+/// assert_eq!(uf.diff(0, 3), None);
+///
+/// assert_eq!(uf.unite(0, 2, &Sum(7)), Ok(false));
+/// assert!(uf.unite(0, 2, &Sum(8)).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct PotentializedQuickUnion<G> {
+  nodes: Vec<Node<G>>,
+  sets_len: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Node<G> {
+  parent: usize,
+  len: usize,
+  // pot(self) = pot(parent).op(&weight)
+  weight: G,
+}
+
+impl<G> PotentializedQuickUnion<G>
+where
+  G: Group,
+{
+  fn is_root(&self, i: usize) -> bool {
+    self.nodes[i].parent == i
+  }
+
+  /// Creates a new `PotentializedQuickUnion` with the given number of elements.
+  ///
+  /// Initially it consists of $n$ singletons:
+  /// $\lbrace \lbrace 0 \rbrace, \lbrace 1 \rbrace, \dots, \lbrace n - 1 \rbrace \rbrace$,
+  /// where $n$ = `len`.
+  ///
+  /// # Time complexity
+  /// $\Theta(n)$
+  pub fn new(len: usize) -> Self {
+    Self {
+      // Initially all nodes are root.
+      nodes: (0..len).map(|i| Node { parent: i, len: 1, weight: G::identity() }).collect(),
+      sets_len: len,
+    }
+  }
+
+  /// Returns the total number of elements that belong to disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Returns the number of disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn sets_len(&self) -> usize {
+    self.sets_len
+  }
+
+  /// Returns the representative of the set that the given element belongs to.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn find(&mut self, i: usize) -> usize {
+    assert_index(i, self.len());
+
+    if self.is_root(i) {
+      return i;
+    }
+    // Path compression
+    // The recursion is shallow since the height of trees is O(log n) by union by size.
+    let parent = self.nodes[i].parent;
+    let root = self.find(parent);
+    if parent != root {
+      self.nodes[i].weight = self.nodes[parent].weight.op(&self.nodes[i].weight);
+      self.nodes[i].parent = root;
+    }
+    root
+  }
+
+  /// Returns the potential of the given element relative to the representative of its set,
+  /// that is, `p` such that `pot(i)` = `pot(self.find(i)).op(&p)`.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn potential(&mut self, i: usize) -> G {
+    assert_index(i, self.len());
+
+    let root = self.find(i);
+    if i == root {
+      G::identity()
+    } else {
+      self.nodes[i].weight.clone()
+    }
+  }
+
+  /// Returns the difference of the potentials of the given elements, that is,
+  /// `d` such that `pot(j)` = `pot(i).op(&d)`, if they belong to the same set.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn diff(&mut self, i: usize, j: usize) -> Option<G> {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    if self.find(i) != self.find(j) {
+      return None;
+    }
+    Some(self.potential(i).invert().op(&self.potential(j)))
+  }
+
+  /// Returns `true` iff the given elements belong to the same set.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn belong_to_same_set(&mut self, i: usize, j: usize) -> bool {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    self.find(i) == self.find(j)
+  }
+
+  /// Returns the number of elements that belong to the same set as the given element.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn set_len(&mut self, i: usize) -> usize {
+    assert_index(i, self.len());
+
+    let i = self.find(i);
+    self.nodes[i].len
+  }
+}
+
+impl<G> PotentializedQuickUnion<G>
+where
+  G: Group + PartialEq,
+{
+  /// Unites two disjoint sets that the given elements belong to into one, with the constraint
+  /// `pot(j)` = `pot(i).op(w)`.
+  ///
+  /// Returns `Ok(false)` iff two elements already belong to the same set and the constraint is
+  /// consistent with the existing ones.
+  ///
+  /// # Errors
+  /// Returns an error iff two elements already belong to the same set and the constraint
+  /// contradicts the existing ones. In this case nothing is changed.
+  ///
+  /// # Time complexity
+  /// $O(\alpha(n))$ amortized
+  pub fn unite(&mut self, i: usize, j: usize, w: &G) -> Result<bool, ContradictionError> {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    let mut ri = self.find(i);
+    let mut rj = self.find(j);
+
+    if ri == rj {
+      return if self.diff(i, j).as_ref() == Some(w) {
+        Ok(false)
+      } else {
+        Err(ContradictionError::new())
+      };
+    }
+
+    // pot(rj) = pot(ri) * potential(i) * w * potential(j)^-1
+    let mut weight = self.potential(i).op(w).op(&self.potential(j).invert());
+    // Union by size
+    if self.nodes[ri].len < self.nodes[rj].len {
+      mem::swap(&mut ri, &mut rj);
+      weight = weight.invert();
+    }
+
+    debug_assert!(self.is_root(ri));
+    debug_assert!(self.is_root(rj));
+    debug_assert!(self.nodes[ri].len >= self.nodes[rj].len);
+
+    self.nodes[rj].parent = ri;
+    self.nodes[rj].weight = weight;
+    self.nodes[ri].len += self.nodes[rj].len;
+    self.sets_len -= 1;
+    Ok(true)
+  }
+}
+
+/// An error returned when a new constraint contradicts the existing ones.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ContradictionError {
+  _priv: (),
+}
+
+impl ContradictionError {
+  pub(crate) fn new() -> Self {
+    Self { _priv: () }
+  }
+}
+
+impl Display for ContradictionError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.write_str("constraint contradicts the existing ones")
+  }
+}
+
+impl Error for ContradictionError {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::algebra::structures::{Monoid, Semigroup};
+  use crate::disjoint_sets::tests::NaiveDisjointSets;
+  use quickcheck::{Arbitrary, Gen};
+  use quickcheck_macros::quickcheck;
+  use rand::seq::SliceRandom as _;
+  use rand::Rng as _;
+  use std::collections::HashSet;
+
+  // The symmetric group of degree 4, which is not commutative.
+  #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+  struct Perm([usize; 4]);
+
+  impl Semigroup for Perm {
+    fn op(&self, rhs: &Self) -> Self {
+      let mut p = rhs.0;
+      for x in &mut p {
+        *x = self.0[*x];
+      }
+      Perm(p)
+    }
+  }
+
+  impl Monoid for Perm {
+    fn identity() -> Self {
+      Perm([0, 1, 2, 3])
+    }
+  }
+
+  impl Group for Perm {
+    fn invert(&self) -> Self {
+      let mut p = [0; 4];
+      for i in 0..4 {
+        p[self.0[i]] = i;
+      }
+      Perm(p)
+    }
+  }
+
+  impl Arbitrary for Perm {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      let mut p = [0, 1, 2, 3];
+      p.shuffle(g);
+      Perm(p)
+    }
+  }
+
+  #[test]
+  fn perm_is_not_commutative() {
+    let a = Perm([1, 0, 2, 3]);
+    let b = Perm([0, 2, 1, 3]);
+    assert_ne!(a.op(&b), b.op(&a));
+  }
+
+  impl From<&PotentializedQuickUnion<Perm>> for NaiveDisjointSets {
+    fn from(uf: &PotentializedQuickUnion<Perm>) -> Self {
+      let mut uf = uf.clone();
+      let mut sets = vec![None::<HashSet<_>>; uf.len()];
+      for i in 0..uf.len() {
+        sets[uf.find(i)].get_or_insert_with(HashSet::new).insert(i);
+      }
+      Self(sets)
+    }
+  }
+
+  #[derive(Clone, Debug)]
+  struct Case {
+    // the potentials which all constraints are consistent with
+    pots: Vec<Perm>,
+    ops: Vec<(usize, usize, Option<Perm>)>,
+  }
+
+  impl Arbitrary for Case {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      let len = g.gen_range(1, 20);
+      let pots = (0..len).map(|_| Perm::arbitrary(g)).collect();
+      let ops = (0..g.gen_range(0, 2 * len))
+        .map(|_| {
+          let w = if g.gen() { Some(Perm::arbitrary(g)) } else { None };
+          (g.gen_range(0, len), g.gen_range(0, len), w)
+        })
+        .collect();
+      Case { pots, ops }
+    }
+  }
+
+  #[quickcheck]
+  fn prop(case: Case) {
+    let Case { pots, ops } = case;
+    let mut uf = PotentializedQuickUnion::new(pots.len());
+    let diff = |i: usize, j: usize| pots[i].invert().op(&pots[j]);
+
+    for (i, j, w) in ops {
+      let old = NaiveDisjointSets::from(&uf);
+      let same = old.find(i) == old.find(j);
+      match w {
+        // a constraint consistent with `pots`
+        None => assert_eq!(uf.unite(i, j, &diff(i, j)), Ok(!same)),
+        // an arbitrary constraint, which is tried only if it can be checked
+        Some(w) if same => {
+          let expected = if w == diff(i, j) { Ok(false) } else { Err(ContradictionError::new()) };
+          assert_eq!(uf.unite(i, j, &w), expected);
+          assert_eq!(NaiveDisjointSets::from(&uf), old);
+        }
+        Some(_) => {}
+      }
+
+      let ds = NaiveDisjointSets::from(&uf);
+      assert_eq!(uf.sets_len(), ds.sets_len());
+      for i in 0..uf.len() {
+        assert_eq!(uf.set_len(i), ds.set_len(i));
+        for j in 0..uf.len() {
+          let same = ds.find(i) == ds.find(j);
+          assert_eq!(uf.belong_to_same_set(i, j), same);
+          assert_eq!(uf.diff(i, j), if same { Some(diff(i, j)) } else { None });
+        }
+      }
+    }
+  }
+}
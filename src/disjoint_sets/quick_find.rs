@@ -1,3 +1,4 @@
+use crate::disjoint_sets::DisjointSets;
 use crate::utils::index_bounds_check::assert_index;
 
 use std::mem;
@@ -110,9 +111,34 @@ impl QuickFind {
   }
 }
 
+impl DisjointSets for QuickFind {
+  fn len(&self) -> usize {
+    QuickFind::len(self)
+  }
+
+  fn sets_len(&self) -> usize {
+    QuickFind::sets_len(self)
+  }
+
+  fn find(&mut self, i: usize) -> usize {
+    QuickFind::find(self, i)
+  }
+
+  fn unite(&mut self, i: usize, j: usize) -> bool {
+    QuickFind::unite(self, i, j)
+  }
+
+  fn set_len(&mut self, i: usize) -> usize {
+    self.set(i).len()
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use super::{super::tests::NaiveDisjointSets, *};
+  use super::{
+    super::tests::{check_disjoint_sets, NaiveDisjointSets},
+    *,
+  };
   use quickcheck::{Arbitrary, Gen};
   use quickcheck_macros::quickcheck;
   use rand::Rng as _;
@@ -223,4 +249,9 @@ mod tests {
     assert_eq!(uf.set(i).len(), ds.set_len(i));
     assert_eq!(&uf.set(i).iter().copied().collect::<HashSet<_>>(), ds.set(i));
   }
+
+  #[quickcheck]
+  fn disjoint_sets_prop(len: usize, ops: Vec<(usize, usize)>) {
+    check_disjoint_sets(QuickFind::new, len, &ops);
+  }
 }
@@ -1,3 +1,4 @@
+use crate::disjoint_sets::DisjointSets;
 use crate::utils::index_bounds_check::assert_index;
 
 use std::mem;
@@ -132,10 +133,32 @@ impl QuickUnion {
   }
 }
 
+impl DisjointSets for QuickUnion {
+  fn len(&self) -> usize {
+    QuickUnion::len(self)
+  }
+
+  fn sets_len(&self) -> usize {
+    QuickUnion::sets_len(self)
+  }
+
+  fn find(&mut self, i: usize) -> usize {
+    QuickUnion::find(self, i)
+  }
+
+  fn unite(&mut self, i: usize, j: usize) -> bool {
+    QuickUnion::unite(self, i, j)
+  }
+
+  fn set_len(&mut self, i: usize) -> usize {
+    QuickUnion::set_len(self, i)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::disjoint_sets::tests::NaiveDisjointSets;
+  use crate::disjoint_sets::tests::{check_disjoint_sets, NaiveDisjointSets};
   use quickcheck::{Arbitrary, Gen};
   use quickcheck_macros::quickcheck;
   use rand::Rng as _;
@@ -272,4 +295,9 @@ mod tests {
     let (ds, len) = assert_unchanged(&mut uf, |uf| uf.set_len(i));
     assert_eq!(len, ds.set_len(i));
   }
+
+  #[quickcheck]
+  fn disjoint_sets_prop(len: usize, ops: Vec<(usize, usize)>) {
+    check_disjoint_sets(QuickUnion::new, len, &ops);
+  }
 }
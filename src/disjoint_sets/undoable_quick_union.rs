@@ -0,0 +1,311 @@
+use crate::disjoint_sets::DisjointSets;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::mem;
+
+/// A disjoint-set data structure based on the weighted quick-union algorithm without path
+/// compression, which can undo unions.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::disjoint_sets::UndoableQuickUnion;
+/// let mut uf = UndoableQuickUnion::new(4);
+/// uf.unite(0, 1);
+///
+/// let snapshot = uf.snapshot();
+/// uf.unite(1, 2);
+/// uf.unite(2, 3);
+/// assert_eq!(uf.sets_len(), 1);
+///
+/// assert!(uf.undo());
+/// assert!(uf.belong_to_same_set(0, 2));
+/// assert!(!uf.belong_to_same_set(0, 3));
+///
+/// uf.rollback(snapshot);
+/// assert!(uf.belong_to_same_set(0, 1));
+/// assert!(!uf.belong_to_same_set(0, 2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct UndoableQuickUnion {
+  nodes: Vec<Node>,
+  sets_len: usize,
+  // `Some((i, j))` if `j` was united to `i` by a call of `unite`, `None` if nothing was performed
+  history: Vec<Option<(usize, usize)>>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+  parent: usize,
+  len: usize,
+}
+
+impl UndoableQuickUnion {
+  fn is_root(&self, i: usize) -> bool {
+    self.nodes[i].parent == i
+  }
+
+  /// Creates a new `UndoableQuickUnion` with the given number of elements.
+  ///
+  /// Initially it consists of $n$ singletons:
+  /// $\lbrace \lbrace 0 \rbrace, \lbrace 1 \rbrace, \dots, \lbrace n - 1 \rbrace \rbrace$,
+  /// where $n$ = `len`.
+  ///
+  /// # Time complexity
+  /// $\Theta(n)$
+  pub fn new(len: usize) -> Self {
+    Self {
+      // Initially all nodes are root.
+      nodes: (0..len).map(|i| Node { parent: i, len: 1 }).collect(),
+      sets_len: len,
+      history: vec![],
+    }
+  }
+
+  /// Returns the total number of elements that belong to disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Returns the number of disjoint sets.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn sets_len(&self) -> usize {
+    self.sets_len
+  }
+
+  /// Returns the representative of the set that the given element belongs to.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn find(&self, mut i: usize) -> usize {
+    assert_index(i, self.len());
+
+    while !self.is_root(i) {
+      i = self.nodes[i].parent;
+    }
+    i
+  }
+
+  /// Unites two disjoint sets that the given elements belong to into one.
+  ///
+  /// Returns `false` iff two elements already belong to the same set.
+  /// Even in that case, the call is recorded as a no-op which [`undo`](Self::undo) reverts.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn unite(&mut self, i: usize, j: usize) -> bool {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    let mut i = self.find(i);
+    let mut j = self.find(j);
+
+    if i == j {
+      self.history.push(None);
+      return false;
+    }
+    // Union by size
+    if self.nodes[i].len < self.nodes[j].len {
+      mem::swap(&mut i, &mut j);
+    }
+
+    debug_assert!(self.is_root(i));
+    debug_assert!(self.is_root(j));
+    debug_assert!(self.nodes[i].len >= self.nodes[j].len);
+
+    self.nodes[j].parent = i;
+    self.nodes[i].len += self.nodes[j].len;
+    self.sets_len -= 1;
+    self.history.push(Some((i, j)));
+    true
+  }
+
+  /// Reverts the last call of [`unite`](Self::unite) which has not been reverted yet.
+  ///
+  /// Returns `false` iff there is no such call.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn undo(&mut self) -> bool {
+    let (i, j) = match self.history.pop() {
+      Some(Some(united)) => united,
+      Some(None) => return true,
+      None => return false,
+    };
+    self.nodes[j].parent = j;
+    self.nodes[i].len -= self.nodes[j].len;
+    self.sets_len += 1;
+    true
+  }
+
+  /// Returns the number of calls of [`unite`](Self::unite) which have not been reverted.
+  ///
+  /// The returned value can be passed to [`rollback`](Self::rollback) to restore the current
+  /// state.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn snapshot(&self) -> usize {
+    self.history.len()
+  }
+
+  /// Reverts calls of [`unite`](Self::unite) until the number of calls which have not been
+  /// reverted is equal to `snapshot`.
+  ///
+  /// # Panics
+  /// Panics if `snapshot` is greater than `self.snapshot()`.
+  ///
+  /// # Time complexity
+  /// $O(k)$ where $k$ = `self.snapshot() - snapshot`
+  pub fn rollback(&mut self, snapshot: usize) {
+    assert!(
+      snapshot <= self.snapshot(),
+      "snapshot out of bounds: the current is {:?} but the snapshot is {:?}",
+      self.snapshot(),
+      snapshot
+    );
+
+    while self.snapshot() > snapshot {
+      self.undo();
+    }
+  }
+
+  /// Returns `true` iff the given elements belong to the same set.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn belong_to_same_set(&self, i: usize, j: usize) -> bool {
+    assert_index(i, self.len());
+    assert_index(j, self.len());
+
+    self.find(i) == self.find(j)
+  }
+
+  /// Returns the number of elements that belong to the same set as the given element.
+  ///
+  /// # Time complexity
+  /// $O(\log(n))$
+  pub fn set_len(&self, i: usize) -> usize {
+    assert_index(i, self.len());
+
+    self.nodes[self.find(i)].len
+  }
+}
+
+impl DisjointSets for UndoableQuickUnion {
+  fn len(&self) -> usize {
+    UndoableQuickUnion::len(self)
+  }
+
+  fn sets_len(&self) -> usize {
+    UndoableQuickUnion::sets_len(self)
+  }
+
+  fn find(&mut self, i: usize) -> usize {
+    UndoableQuickUnion::find(self, i)
+  }
+
+  fn unite(&mut self, i: usize, j: usize) -> bool {
+    UndoableQuickUnion::unite(self, i, j)
+  }
+
+  fn set_len(&mut self, i: usize) -> usize {
+    UndoableQuickUnion::set_len(self, i)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::disjoint_sets::tests::{check_disjoint_sets, NaiveDisjointSets};
+  use quickcheck::{Arbitrary, Gen};
+  use quickcheck_macros::quickcheck;
+  use rand::Rng as _;
+  use std::collections::HashSet;
+
+  impl From<&UndoableQuickUnion> for NaiveDisjointSets {
+    fn from(uf: &UndoableQuickUnion) -> Self {
+      let mut sets = vec![None; uf.len()];
+      for i in 0..uf.len() {
+        sets[uf.find(i)].get_or_insert_with(HashSet::new).insert(i);
+      }
+      Self(sets)
+    }
+  }
+
+  #[derive(Clone, Debug)]
+  enum Op {
+    Unite(usize, usize),
+    Undo,
+    Snapshot,
+    Rollback,
+  }
+
+  impl Arbitrary for Op {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      match g.gen_range(0, 8) {
+        0..=3 => Op::Unite(g.gen(), g.gen()),
+        4 | 5 => Op::Undo,
+        6 => Op::Snapshot,
+        _ => Op::Rollback,
+      }
+    }
+  }
+
+  #[quickcheck]
+  fn prop(len: usize, ops: Vec<Op>) {
+    let len = len % 50 + 1;
+    let mut uf = UndoableQuickUnion::new(len);
+    // the states before each call of `unite`
+    let mut history = vec![];
+    let mut snapshots = vec![];
+
+    for op in ops {
+      match op {
+        Op::Unite(i, j) => {
+          let (i, j) = (i % len, j % len);
+          let old = NaiveDisjointSets::from(&uf);
+          assert_eq!(uf.unite(i, j), old.find(i) != old.find(j));
+          history.push(old);
+        }
+        Op::Undo => match history.pop() {
+          Some(old) => {
+            assert!(uf.undo());
+            assert_eq!(NaiveDisjointSets::from(&uf), old);
+          }
+          None => assert!(!uf.undo()),
+        },
+        Op::Snapshot => {
+          assert_eq!(uf.snapshot(), history.len());
+          snapshots.push(uf.snapshot());
+        }
+        Op::Rollback => {
+          snapshots.retain(|&s| s <= history.len());
+          if let Some(s) = snapshots.pop() {
+            let expected = history.get(s).cloned().unwrap_or_else(|| (&uf).into());
+            uf.rollback(s);
+            history.truncate(s);
+            assert_eq!(uf.snapshot(), s);
+            assert_eq!(NaiveDisjointSets::from(&uf), expected);
+          }
+        }
+      }
+
+      let ds = NaiveDisjointSets::from(&uf);
+      assert_eq!(uf.sets_len(), ds.sets_len());
+      for i in 0..len {
+        assert_eq!(uf.set_len(i), ds.set_len(i));
+      }
+    }
+  }
+
+  #[quickcheck]
+  fn disjoint_sets_prop(len: usize, ops: Vec<(usize, usize)>) {
+    check_disjoint_sets(UndoableQuickUnion::new, len, &ops);
+  }
+}
@@ -0,0 +1,9 @@
+//! Graph algorithms.
+
+pub use self::dynamic_connectivity::*;
+pub use self::minimum_spanning_arborescence::*;
+pub use self::minimum_spanning_tree::*;
+
+mod dynamic_connectivity;
+mod minimum_spanning_arborescence;
+mod minimum_spanning_tree;
@@ -0,0 +1,258 @@
+use crate::disjoint_sets::UndoableQuickUnion;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::collections::HashMap;
+
+/// An operation on an undirected graph given to [`offline_dynamic_connectivity`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DynamicConnectivityQuery {
+  /// Inserts an edge between two vertices. Parallel edges and self-loops are allowed.
+  InsertEdge(usize, usize),
+  /// Removes one of the edges between two vertices.
+  RemoveEdge(usize, usize),
+  /// Asks whether two vertices are connected.
+  Connected(usize, usize),
+  /// Asks the number of connected components.
+  ComponentsLen,
+}
+
+/// An answer to [`DynamicConnectivityQuery::Connected`] or
+/// [`DynamicConnectivityQuery::ComponentsLen`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DynamicConnectivityAnswer {
+  /// Whether the two vertices are connected.
+  Connected(bool),
+  /// The number of connected components.
+  ComponentsLen(usize),
+}
+
+/// Performs the given operations in order on an undirected graph with `len` vertices and no
+/// edges, and answers the connectivity queries among them offline.
+///
+/// Returns the answers in the order of the corresponding queries.
+///
+/// Each edge is registered on the segment tree over time by its lifetime, and the tree is
+/// traversed with [`UndoableQuickUnion`], uniting the endpoints on entering a node and rolling
+/// them back on leaving it.
+///
+/// # Panics
+/// Panics if any vertex is out of bounds, or an edge to be removed does not exist.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::graph::{offline_dynamic_connectivity, DynamicConnectivityAnswer as A};
+/// # use k7lib::graph::DynamicConnectivityQuery::*;
+/// let answers = offline_dynamic_connectivity(
+///   3,
+///   &[
+///     InsertEdge(0, 1),
+///     InsertEdge(1, 2),
+///     Connected(0, 2),
+///     RemoveEdge(1, 2),
+///     Connected(0, 2),
+///     ComponentsLen,
+///   ],
+/// );
+/// assert_eq!(answers, [A::Connected(true), A::Connected(false), A::ComponentsLen(2)]);
+/// ```
+///
+/// # Time complexity
+/// $O(n + q \log(q) \log(n))$ where $q$ = `queries.len()`
+///
+/// # References
+///
+/// * [Dynamic connectivity - Wikipedia](https://en.wikipedia.org/w/index.php?title=Dynamic_connectivity&oldid=964428823)
+pub fn offline_dynamic_connectivity(
+  len: usize,
+  queries: &[DynamicConnectivityQuery],
+) -> Vec<DynamicConnectivityAnswer> {
+  use self::DynamicConnectivityQuery::*;
+
+  let time_len = queries.len();
+  let size = time_len.next_power_of_two();
+  // `edges[k]` is the edges alive throughout the time interval of node `k`
+  let mut edges = vec![vec![]; 2 * size];
+  let mut add = |mut l: usize, mut r: usize, e: (usize, usize)| {
+    l += size;
+    r += size;
+    while l < r {
+      if l % 2 == 1 {
+        edges[l].push(e);
+        l += 1;
+      }
+      if r % 2 == 1 {
+        r -= 1;
+        edges[r].push(e);
+      }
+      l /= 2;
+      r /= 2;
+    }
+  };
+
+  // the times when each alive edge was inserted
+  let mut inserted = HashMap::<_, Vec<_>>::new();
+  for (t, query) in queries.iter().enumerate() {
+    match *query {
+      InsertEdge(u, v) => {
+        assert_index(u, len);
+        assert_index(v, len);
+        inserted.entry((u.min(v), u.max(v))).or_default().push(t);
+      }
+      RemoveEdge(u, v) => {
+        let e = (u.min(v), u.max(v));
+        let s = inserted
+          .get_mut(&e)
+          .and_then(Vec::pop)
+          .unwrap_or_else(|| panic!("the edge to be removed does not exist: {:?}", (u, v)));
+        add(s, t, e);
+      }
+      Connected(u, v) => {
+        assert_index(u, len);
+        assert_index(v, len);
+      }
+      ComponentsLen => {}
+    }
+  }
+  for (e, ts) in inserted {
+    for s in ts {
+      add(s, time_len, e);
+    }
+  }
+
+  let mut uf = UndoableQuickUnion::new(len);
+  let mut answers = Vec::with_capacity(time_len);
+  if time_len != 0 {
+    traverse(1, 0, size, &edges, queries, &mut uf, &mut answers);
+  }
+  answers
+}
+
+// Visits node `k` whose time interval is `[l, l + width)`.
+fn traverse(
+  k: usize,
+  l: usize,
+  width: usize,
+  edges: &[Vec<(usize, usize)>],
+  queries: &[DynamicConnectivityQuery],
+  uf: &mut UndoableQuickUnion,
+  answers: &mut Vec<DynamicConnectivityAnswer>,
+) {
+  if l >= queries.len() {
+    return;
+  }
+
+  let snapshot = uf.snapshot();
+  for &(u, v) in &edges[k] {
+    uf.unite(u, v);
+  }
+  if width == 1 {
+    match queries[l] {
+      DynamicConnectivityQuery::Connected(u, v) => {
+        answers.push(DynamicConnectivityAnswer::Connected(uf.belong_to_same_set(u, v)))
+      }
+      DynamicConnectivityQuery::ComponentsLen => {
+        answers.push(DynamicConnectivityAnswer::ComponentsLen(uf.sets_len()))
+      }
+      _ => {}
+    }
+  } else {
+    let width = width / 2;
+    traverse(2 * k, l, width, edges, queries, uf, answers);
+    traverse(2 * k + 1, l + width, width, edges, queries, uf, answers);
+  }
+  uf.rollback(snapshot);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DynamicConnectivityQuery::*;
+  use super::*;
+  use crate::disjoint_sets::QuickUnion;
+  use quickcheck::{Arbitrary, Gen};
+  use quickcheck_macros::quickcheck;
+  use rand::seq::SliceRandom as _;
+  use rand::Rng as _;
+
+  #[derive(Clone, Debug)]
+  struct Case {
+    len: usize,
+    queries: Vec<DynamicConnectivityQuery>,
+  }
+
+  impl Arbitrary for Case {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      let len = g.gen_range(1, 10);
+      let mut alive = vec![];
+      let queries = (0..g.gen_range(0, 60))
+        .map(|_| match g.gen_range(0, 4) {
+          0 => {
+            let e = (g.gen_range(0, len), g.gen_range(0, len));
+            alive.push(e);
+            InsertEdge(e.0, e.1)
+          }
+          1 if !alive.is_empty() => {
+            alive.shuffle(g);
+            let (u, v) = alive.pop().unwrap();
+            if g.gen() {
+              RemoveEdge(u, v)
+            } else {
+              RemoveEdge(v, u)
+            }
+          }
+          2 => Connected(g.gen_range(0, len), g.gen_range(0, len)),
+          _ => ComponentsLen,
+        })
+        .collect();
+      Case { len, queries }
+    }
+  }
+
+  fn naive(len: usize, queries: &[DynamicConnectivityQuery]) -> Vec<DynamicConnectivityAnswer> {
+    let mut edges = vec![];
+    let mut answers = vec![];
+    let uf = |edges: &[(usize, usize)]| {
+      let mut uf = QuickUnion::new(len);
+      for &(u, v) in edges {
+        uf.unite(u, v);
+      }
+      uf
+    };
+    for query in queries {
+      match *query {
+        InsertEdge(u, v) => edges.push((u.min(v), u.max(v))),
+        RemoveEdge(u, v) => {
+          let i = edges.iter().position(|&e| e == (u.min(v), u.max(v))).unwrap();
+          edges.remove(i);
+        }
+        Connected(u, v) => {
+          answers.push(DynamicConnectivityAnswer::Connected(uf(&edges).belong_to_same_set(u, v)))
+        }
+        ComponentsLen => {
+          answers.push(DynamicConnectivityAnswer::ComponentsLen(uf(&edges).sets_len()))
+        }
+      }
+    }
+    answers
+  }
+
+  #[quickcheck]
+  fn prop(case: Case) {
+    let Case { len, queries } = case;
+    assert_eq!(offline_dynamic_connectivity(len, &queries), naive(len, &queries));
+  }
+
+  #[test]
+  fn no_vertices() {
+    assert_eq!(
+      offline_dynamic_connectivity(0, &[ComponentsLen]),
+      [DynamicConnectivityAnswer::ComponentsLen(0)]
+    );
+  }
+
+  #[test]
+  #[should_panic]
+  fn remove_nonexistent_edge() {
+    offline_dynamic_connectivity(2, &[InsertEdge(0, 1), RemoveEdge(0, 1), RemoveEdge(1, 0)]);
+  }
+}
@@ -0,0 +1,211 @@
+use crate::collections::LeftistHeap;
+use crate::disjoint_sets::UndoableQuickUnion;
+use crate::num::primitive::SignedInt;
+use crate::utils::index_bounds_check::assert_index;
+
+/// Finds a minimum spanning arborescence of a directed graph rooted at `root` by the Chu–Liu/
+/// Edmonds algorithm, that is, a minimum weight set of edges such that every vertex is reachable
+/// from `root` by a unique path.
+///
+/// The graph has `vertices_len` vertices and the edges `(u, v, w)` from `u` to `v` of weight
+/// `w`. Returns the indices of the chosen edges in ascending order, and their total weight, or
+/// `None` if some vertex is unreachable from `root`.
+///
+/// This is Tarjan's implementation, which contracts cycles with [`UndoableQuickUnion`] and
+/// [`LeftistHeap`]s of incoming edges, and expands them by rolling back the unions.
+///
+/// The weights must be signed because the weight of each chosen edge is subtracted from all the
+/// other incoming edges of its component by adding its negation to the heap lazily.
+///
+/// # Panics
+/// Panics if `root` or any vertex is out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::graph::minimum_spanning_arborescence;
+/// let edges = [(0, 1, 5), (1, 2, 1), (2, 1, 1), (0, 2, 3), (2, 3, 4)];
+/// assert_eq!(minimum_spanning_arborescence(4, &edges, 0), Some((vec![2, 3, 4], 8)));
+/// assert_eq!(minimum_spanning_arborescence(4, &edges, 1), None);
+/// ```
+///
+/// # Time complexity
+/// $O(n + m \log(m))$ where $m$ = `edges.len()`
+///
+/// # References
+///
+/// * [Tarjan, R. E. (1977). Finding optimum branchings. Networks, 7(1), 25–35.][1]
+/// * [Edmonds' algorithm - Wikipedia][2]
+///
+/// [1]: https://doi.org/10.1002/net.3230070103
+/// [2]: https://en.wikipedia.org/w/index.php?title=Edmonds%27_algorithm&oldid=958223226
+pub fn minimum_spanning_arborescence<W>(
+  vertices_len: usize,
+  edges: &[(usize, usize, W)],
+  root: usize,
+) -> Option<(Vec<usize>, W)>
+where
+  W: SignedInt,
+{
+  assert_index(root, vertices_len);
+
+  let n = vertices_len;
+  // the incoming edges of each component, whose weights are reduced by the chosen ones
+  let mut heaps = (0..n).map(|_| LeftistHeap::new()).collect::<Vec<_>>();
+  for (k, &(u, v, w)) in edges.iter().enumerate() {
+    assert_index(u, n);
+    assert_index(v, n);
+    heaps[v].push(w, k);
+  }
+
+  let mut uf = UndoableQuickUnion::new(n);
+  // `seen[u]` is the starting vertex of the path through `u`
+  let mut seen = vec![None; n];
+  seen[root] = Some(root);
+  let mut incoming = vec![None; n];
+  // the contracted cycles and the states of `uf` before contracting them
+  let mut cycles = vec![];
+
+  for s in 0..n {
+    let mut u = s;
+    // the components on the path and the edges chosen for them
+    let mut path = vec![];
+    while seen[u].is_none() {
+      let (w, k) = heaps[u].pop()?;
+      heaps[u].add_all(-w);
+      path.push((u, k));
+      seen[u] = Some(s);
+      u = uf.find(edges[k].0);
+
+      if seen[u] == Some(s) {
+        // Contracts the cycle, which may be a self-loop of the component.
+        let snapshot = uf.snapshot();
+        let mut heap = LeftistHeap::new();
+        let mut i = path.len();
+        loop {
+          i -= 1;
+          let v = path[i].0;
+          heap.append(&mut heaps[v]);
+          if !uf.unite(u, v) {
+            break;
+          }
+        }
+        u = uf.find(u);
+        heaps[u] = heap;
+        seen[u] = None;
+        cycles.push((u, snapshot, path.drain(i..).map(|(_, k)| k).collect::<Vec<_>>()));
+      }
+    }
+    for &(_, k) in &path {
+      incoming[uf.find(edges[k].1)] = Some(k);
+    }
+  }
+
+  // Expands the cycles in the reverse order. In each cycle, the edge entering the vertex which
+  // the incoming edge of the cycle enters is replaced.
+  for (u, snapshot, cycle) in cycles.into_iter().rev() {
+    uf.rollback(snapshot);
+    let k = incoming[u].unwrap();
+    for l in cycle {
+      incoming[uf.find(edges[l].1)] = Some(l);
+    }
+    incoming[uf.find(edges[k].1)] = Some(k);
+  }
+
+  let mut chosen = (0..n).filter(|&v| v != root).map(|v| incoming[v].unwrap()).collect::<Vec<_>>();
+  chosen.sort();
+  let weight = chosen.iter().fold(W::ZERO, |acc, &k| acc + edges[k].2);
+  Some((chosen, weight))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck::{Arbitrary, Gen};
+  use quickcheck_macros::quickcheck;
+  use rand::Rng as _;
+
+  #[derive(Clone, Debug)]
+  struct Case {
+    vertices_len: usize,
+    edges: Vec<(usize, usize, i64)>,
+    root: usize,
+  }
+
+  impl Arbitrary for Case {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      let vertices_len = g.gen_range(1, 6);
+      let edges = (0..g.gen_range(0, 12))
+        .map(|_| (g.gen_range(0, vertices_len), g.gen_range(0, vertices_len), g.gen_range(-5, 5)))
+        .collect();
+      Case { vertices_len, edges, root: g.gen_range(0, vertices_len) }
+    }
+  }
+
+  // Returns whether the edges form an arborescence rooted at `root`, where `parents[v]` is the
+  // edge entering `v`.
+  fn is_arborescence(
+    edges: &[(usize, usize, i64)],
+    parents: &[Option<usize>],
+    root: usize,
+  ) -> bool {
+    (0..parents.len()).all(|mut v| {
+      for _ in 0..parents.len() {
+        if v == root {
+          return true;
+        }
+        v = edges[parents[v].unwrap()].0;
+      }
+      false
+    })
+  }
+
+  fn naive(vertices_len: usize, edges: &[(usize, usize, i64)], root: usize) -> Option<i64> {
+    // Tries all the choices of an incoming edge for each vertex other than `root`.
+    fn go(
+      v: usize,
+      edges: &[(usize, usize, i64)],
+      root: usize,
+      parents: &mut Vec<Option<usize>>,
+      best: &mut Option<i64>,
+    ) {
+      if v == parents.len() {
+        if is_arborescence(edges, parents, root) {
+          let weight = parents.iter().flatten().map(|&k| edges[k].2).sum();
+          *best = Some(best.map_or(weight, |best| best.min(weight)));
+        }
+        return;
+      }
+      if v == root {
+        return go(v + 1, edges, root, parents, best);
+      }
+      for k in (0..edges.len()).filter(|&k| edges[k].1 == v) {
+        parents[v] = Some(k);
+        go(v + 1, edges, root, parents, best);
+      }
+      parents[v] = None;
+    }
+
+    let mut best = None;
+    go(0, edges, root, &mut vec![None; vertices_len], &mut best);
+    best
+  }
+
+  #[quickcheck]
+  fn prop(case: Case) {
+    let Case { vertices_len, edges, root } = case;
+    let result = minimum_spanning_arborescence(vertices_len, &edges, root);
+    assert_eq!(result.as_ref().map(|&(_, weight)| weight), naive(vertices_len, &edges, root));
+
+    if let Some((chosen, weight)) = result {
+      assert!(chosen.windows(2).all(|w| w[0] < w[1]));
+      assert_eq!(weight, chosen.iter().map(|&k| edges[k].2).sum());
+      let mut parents = vec![None; vertices_len];
+      for &k in &chosen {
+        assert_ne!(edges[k].1, root);
+        assert!(parents[edges[k].1].replace(k).is_none());
+      }
+      assert!(is_arborescence(&edges, &parents, root));
+    }
+  }
+}
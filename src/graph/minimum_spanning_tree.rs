@@ -0,0 +1,264 @@
+use crate::disjoint_sets::QuickUnion;
+use crate::utils::index_bounds_check::assert_index;
+
+use std::iter::Sum;
+
+fn check_edges<W>(vertices_len: usize, edges: &[(usize, usize, W)]) {
+  for &(u, v, _) in edges {
+    assert_index(u, vertices_len);
+    assert_index(v, vertices_len);
+  }
+}
+
+fn finish<W>(edges: &[(usize, usize, W)], mut chosen: Vec<usize>) -> (Vec<usize>, W)
+where
+  W: Clone + Sum,
+{
+  chosen.sort();
+  let weight = chosen.iter().map(|&k| edges[k].2.clone()).sum();
+  (chosen, weight)
+}
+
+/// Finds a minimum spanning forest of an undirected graph by Kruskal's algorithm.
+///
+/// The graph has `vertices_len` vertices and the edges `(u, v, w)` between `u` and `v` of weight
+/// `w`. Returns the indices of the chosen edges in ascending order, and their total weight.
+///
+/// # Panics
+/// Panics if any vertex is out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::graph::kruskal;
+/// let edges = [(0, 1, 3), (1, 2, 1), (0, 2, 2), (3, 4, 5)];
+/// assert_eq!(kruskal(5, &edges), (vec![1, 2, 3], 8));
+/// ```
+///
+/// # Time complexity
+/// $O(n + m \log(m))$ where $m$ = `edges.len()`
+///
+/// # References
+///
+/// * [Kruskal's algorithm - Wikipedia](https://en.wikipedia.org/w/index.php?title=Kruskal%27s_algorithm&oldid=965742843)
+pub fn kruskal<W>(vertices_len: usize, edges: &[(usize, usize, W)]) -> (Vec<usize>, W)
+where
+  W: Ord + Clone + Sum,
+{
+  check_edges(vertices_len, edges);
+
+  let mut order = (0..edges.len()).collect::<Vec<_>>();
+  order.sort_by(|&k, &l| edges[k].2.cmp(&edges[l].2));
+  let mut uf = QuickUnion::new(vertices_len);
+  let chosen = order.into_iter().filter(|&k| uf.unite(edges[k].0, edges[k].1)).collect();
+  finish(edges, chosen)
+}
+
+/// Finds a minimum spanning forest of an undirected graph by Prim's algorithm, which is suitable
+/// for dense graphs.
+///
+/// The graph has `vertices_len` vertices and the edges `(u, v, w)` between `u` and `v` of weight
+/// `w`. Returns the indices of the chosen edges in ascending order, and their total weight.
+///
+/// # Panics
+/// Panics if any vertex is out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::graph::prim;
+/// let edges = [(0, 1, 3), (1, 2, 1), (0, 2, 2), (3, 4, 5)];
+/// assert_eq!(prim(5, &edges), (vec![1, 2, 3], 8));
+/// ```
+///
+/// # Time complexity
+/// $O(n^2 + m)$ where $m$ = `edges.len()`
+///
+/// # References
+///
+/// * [Prim's algorithm - Wikipedia](https://en.wikipedia.org/w/index.php?title=Prim%27s_algorithm&oldid=966190779)
+pub fn prim<W>(vertices_len: usize, edges: &[(usize, usize, W)]) -> (Vec<usize>, W)
+where
+  W: Ord + Clone + Sum,
+{
+  check_edges(vertices_len, edges);
+
+  let n = vertices_len;
+  // the indices of the edges incident to each vertex
+  let mut adj = vec![vec![]; n];
+  for (k, &(u, v, _)) in edges.iter().enumerate() {
+    adj[u].push(k);
+    if u != v {
+      adj[v].push(k);
+    }
+  }
+
+  let mut used = vec![false; n];
+  // the lightest edge between each vertex and the used ones
+  let mut lightest = vec![None; n];
+  let mut chosen = vec![];
+  for _ in 0..n {
+    // Starts a new tree if no unused vertex is adjacent to the used ones.
+    let u = (0..n)
+      .filter(|&u| !used[u])
+      .min_by_key(|&u| (lightest[u].is_none(), lightest[u].map(|k: usize| &edges[k].2)))
+      .unwrap();
+    used[u] = true;
+    chosen.extend(lightest[u]);
+    for &k in &adj[u] {
+      let v = if edges[k].0 == u { edges[k].1 } else { edges[k].0 };
+      if !used[v] && lightest[v].map_or(true, |l: usize| edges[l].2 > edges[k].2) {
+        lightest[v] = Some(k);
+      }
+    }
+  }
+  finish(edges, chosen)
+}
+
+/// Finds a minimum spanning forest of an undirected graph by Borůvka's algorithm.
+///
+/// The graph has `vertices_len` vertices and the edges `(u, v, w)` between `u` and `v` of weight
+/// `w`. Returns the indices of the chosen edges in ascending order, and their total weight.
+///
+/// # Panics
+/// Panics if any vertex is out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::graph::boruvka;
+/// let edges = [(0, 1, 3), (1, 2, 1), (0, 2, 2), (3, 4, 5)];
+/// assert_eq!(boruvka(5, &edges), (vec![1, 2, 3], 8));
+/// ```
+///
+/// # Time complexity
+/// $O(n + m \log(n))$ where $m$ = `edges.len()`
+///
+/// # References
+///
+/// * [Borůvka's algorithm - Wikipedia](https://en.wikipedia.org/w/index.php?title=Bor%C5%AFvka%27s_algorithm&oldid=962990307)
+pub fn boruvka<W>(vertices_len: usize, edges: &[(usize, usize, W)]) -> (Vec<usize>, W)
+where
+  W: Ord + Clone + Sum,
+{
+  check_edges(vertices_len, edges);
+
+  let mut uf = QuickUnion::new(vertices_len);
+  let mut chosen = vec![];
+  loop {
+    // the lightest edge leaving each component, where ties are broken by the indices so that
+    // the chosen edges never make a cycle
+    let mut lightest = vec![None; vertices_len];
+    for (k, &(u, v, ref w)) in edges.iter().enumerate() {
+      let (u, v) = (uf.find(u), uf.find(v));
+      if u == v {
+        continue;
+      }
+      for &r in &[u, v] {
+        if lightest[r].map_or(true, |l: usize| (&edges[l].2, l) > (w, k)) {
+          lightest[r] = Some(k);
+        }
+      }
+    }
+
+    let len = chosen.len();
+    for k in lightest.into_iter().flatten() {
+      if uf.unite(edges[k].0, edges[k].1) {
+        chosen.push(k);
+      }
+    }
+    if chosen.len() == len {
+      break;
+    }
+  }
+  finish(edges, chosen)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck::{Arbitrary, Gen};
+  use quickcheck_macros::quickcheck;
+  use rand::Rng as _;
+
+  #[derive(Clone, Debug)]
+  struct Case {
+    vertices_len: usize,
+    edges: Vec<(usize, usize, i64)>,
+  }
+
+  impl Arbitrary for Case {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+      let vertices_len = g.gen_range(0, 7);
+      let edges = if vertices_len == 0 {
+        vec![]
+      } else {
+        (0..g.gen_range(0, 11))
+          .map(|_| (g.gen_range(0, vertices_len), g.gen_range(0, vertices_len), g.gen_range(-5, 5)))
+          .collect()
+      };
+      Case { vertices_len, edges }
+    }
+  }
+
+  // Returns the number of edges and the total weight of a minimum spanning forest.
+  fn naive(vertices_len: usize, edges: &[(usize, usize, i64)]) -> (usize, i64) {
+    let components_len = {
+      let mut uf = QuickUnion::new(vertices_len);
+      for &(u, v, _) in edges {
+        uf.unite(u, v);
+      }
+      uf.sets_len()
+    };
+    let len = vertices_len - components_len;
+    let weight = (0..1 << edges.len())
+      .filter(|&s: &u32| s.count_ones() as usize == len)
+      .filter_map(|s| {
+        let mut uf = QuickUnion::new(vertices_len);
+        let mut weight = 0;
+        for (k, &(u, v, w)) in edges.iter().enumerate() {
+          if s >> k & 1 == 1 {
+            if !uf.unite(u, v) {
+              return None;
+            }
+            weight += w;
+          }
+        }
+        Some(weight)
+      })
+      .min()
+      .unwrap();
+    (len, weight)
+  }
+
+  fn test<F>(mst: F, case: Case)
+  where
+    F: FnOnce(usize, &[(usize, usize, i64)]) -> (Vec<usize>, i64),
+  {
+    let Case { vertices_len, edges } = case;
+    let (chosen, weight) = mst(vertices_len, &edges);
+
+    assert!(chosen.windows(2).all(|w| w[0] < w[1]));
+    let mut uf = QuickUnion::new(vertices_len);
+    for &k in &chosen {
+      assert!(uf.unite(edges[k].0, edges[k].1));
+    }
+    assert_eq!(weight, chosen.iter().map(|&k| edges[k].2).sum());
+    assert_eq!((chosen.len(), weight), naive(vertices_len, &edges));
+  }
+
+  #[quickcheck]
+  fn kruskal_prop(case: Case) {
+    test(kruskal, case);
+  }
+
+  #[quickcheck]
+  fn prim_prop(case: Case) {
+    test(prim, case);
+  }
+
+  #[quickcheck]
+  fn boruvka_prop(case: Case) {
+    test(boruvka, case);
+  }
+}
@@ -7,8 +7,10 @@ mod utils;
 pub mod algebra;
 pub mod byte;
 pub mod cmp;
+pub mod collections;
 pub mod constant;
 pub mod disjoint_sets;
+pub mod graph;
 pub mod io;
 pub mod num;
 pub mod sequences;
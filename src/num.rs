@@ -5,6 +5,7 @@ pub use self::div_floor::*;
 pub use self::gcd::*;
 pub use self::lcm::*;
 pub use self::midpoint::*;
+pub use self::ratio::*;
 
 mod div_ceil;
 mod div_floor;
@@ -12,3 +13,4 @@ mod gcd;
 mod lcm;
 mod midpoint;
 pub mod primitive;
+mod ratio;
@@ -1,4 +1,5 @@
 use crate::cmp::Bounded;
+use std::convert::{TryFrom, TryInto};
 use std::{fmt, hash, iter, num::ParseIntError, ops, str};
 
 // for cargo-simple-bundler
@@ -61,12 +62,34 @@ macro_rules! int {
 pub trait SignedInt {}
 
 macro_rules! signed_int {
-  ($($func:tt)*) => {
+  ($($T:ty => $Unsigned:ty),*; $($func:tt)*) => {
     /// A trait for primitive signed integer types.
     pub trait SignedInt: Int + ops::Neg<Output = Self> {
+      /// The unsigned integer type with the same width as `Self`.
+      type Unsigned: UnsignedInt + TryFrom<Self> + TryInto<Self>;
+
+      /// Returns the absolute value as `Self::Unsigned`, which never overflows.
+      fn unsigned_abs(self) -> Self::Unsigned;
+
       $($func)*
     }
-    impls! { SignedInt => i8, i16, i32, i64, i128, isize; $($func)* }
+    signed_int_impls! { $($T => $Unsigned),*; $($func)* }
+  };
+}
+
+macro_rules! signed_int_impls {
+  (; $($item:tt)*) => {};
+  ($T:ty => $Unsigned:ty $(, $U:ty => $UUnsigned:ty)*; $($item:tt)*) => {
+    impl SignedInt for $T {
+      type Unsigned = $Unsigned;
+
+      fn unsigned_abs(self) -> $Unsigned {
+        self.wrapping_abs() as $Unsigned
+      }
+
+      items! { $($item)* }
+    }
+    signed_int_impls! { $($U => $UUnsigned),*; $($item)* }
   };
 }
 
@@ -75,15 +98,56 @@ macro_rules! signed_int {
 pub trait UnsignedInt {}
 
 macro_rules! unsigned_int {
-  ($($func:tt)*) => {
+  ($($T:ty => $Double:ty),*; $($func:tt)*) => {
     /// A trait for primitive unsigned integer types.
-    pub trait UnsignedInt: Int {
+    pub trait UnsignedInt: Int + WideMul {
       $($func)*
     }
-    impls! { UnsignedInt => u8, u16, u32, u64, u128, usize; $($func)* }
+    unsigned_int_impls! { $($T => $Double),*; $($func)* }
   };
 }
 
+macro_rules! unsigned_int_impls {
+  (; $($item:tt)*) => {};
+  ($T:ty => $Double:ty $(, $U:ty => $UDouble:ty)*; $($item:tt)*) => {
+    impl UnsignedInt for $T {
+      items! { $($item)* }
+    }
+
+    impl WideMul for $T {
+      type Double = $Double;
+
+      fn mul_wide(self, rhs: Self) -> $Double {
+        (self as $Double).checked_mul(rhs as $Double).expect("multiplication overflowed")
+      }
+    }
+
+    unsigned_int_impls! { $($U => $UDouble),*; $($item)* }
+  };
+}
+
+/// A trait for multiplying primitive unsigned integers without overflow.
+pub trait WideMul: Sized {
+  /// The unsigned integer type with twice the width of `Self`.
+  ///
+  /// For `u128`, which has no wider primitive type, this is `u128` itself. For `usize`, this is
+  /// `u128` regardless of the target, which is wider than twice on 32-bit targets.
+  type Double: UnsignedInt + TryFrom<Self> + TryInto<Self>;
+
+  /// Multiplies two integers into `Self::Double`.
+  ///
+  /// # Panics
+  /// Panics if the product overflows `Self::Double`, which can happen only for `u128`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// # use k7lib::num::primitive::WideMul;
+  /// assert_eq!(u64::max_value().mul_wide(2), 2 * u128::from(u64::max_value()));
+  /// ```
+  fn mul_wide(self, rhs: Self) -> Self::Double;
+}
+
 macro_rules! impls {
   ($Trait:ty => ; $($item:tt)*) => {};
   ($Trait:ty => $T:ty $(, $U:ty)*; $($item:tt)*) => {
@@ -180,6 +244,12 @@ int! {
 }
 
 signed_int! {
+  i8 => u8,
+  i16 => u16,
+  i32 => u32,
+  i64 => u64,
+  i128 => u128,
+  isize => usize;
   fn checked_abs(self) -> Option<Self>;
   fn wrapping_abs(self) -> Self;
   fn overflowing_abs(self) -> (Self, bool);
@@ -190,6 +260,12 @@ signed_int! {
 }
 
 unsigned_int! {
+  u8 => u16,
+  u16 => u32,
+  u32 => u64,
+  u64 => u128,
+  u128 => u128,
+  usize => u128;
   fn is_power_of_two(self) -> bool;
   fn next_power_of_two(self) -> Self;
   fn checked_next_power_of_two(self) -> Option<Self>;
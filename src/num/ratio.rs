@@ -0,0 +1,485 @@
+use super::gcd;
+use super::primitive::{Int as PrimInt, SignedInt as PrimSint};
+use crate::algebra::structures::{CommutativeSemiring, Field, Ring, Semiring};
+
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// A rational number, kept in lowest terms with a positive denominator.
+///
+/// All the operations are exact. The `checked_*` methods return `None` on overflow, and the
+/// operators panic on overflow.
+///
+/// # Examples
+///
+/// ```
+/// # use k7lib::num::Ratio;
+/// let x = Ratio::new(6i64, -4);
+/// assert_eq!((x.numer(), x.denom()), (-3, 2));
+/// assert_eq!(x + Ratio::new(1, 3), Ratio::new(-7, 6));
+/// assert_eq!("0.25".parse(), Ok(Ratio::new(1i64, 4)));
+/// assert_eq!(x.to_string(), "-3/2");
+/// assert!(Ratio::new(1i64, 3) < Ratio::new(1, 2));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Ratio<Int> {
+  numer: Int,
+  denom: Int,
+}
+
+impl<Int> Ratio<Int>
+where
+  Int: PrimSint,
+{
+  // Returns `None` if `denom` is zero or the reduced fraction is not representable.
+  // Rust 1.39 cannot resolve `Int::Unsigned / Int::Unsigned`, only the one with a reference.
+  #[allow(clippy::op_ref)]
+  fn reduce(numer: Int, denom: Int) -> Option<Self> {
+    if denom == Int::ZERO {
+      return None;
+    }
+    let (n, d) = (numer.unsigned_abs(), denom.unsigned_abs());
+    let g = gcd(n, d);
+    let (n, d) = (n / &g, d / &g);
+    let numer =
+      if numer.is_negative() != denom.is_negative() && n != <Int::Unsigned as PrimInt>::ZERO {
+        // `n` may be the absolute value of `Int::MIN`
+        let n: Int = (n - &<Int::Unsigned as PrimInt>::ONE).try_into().ok()?;
+        -n - Int::ONE
+      } else {
+        n.try_into().ok()?
+      };
+    Some(Ratio { numer, denom: d.try_into().ok()? })
+  }
+
+  /// Creates a new `Ratio` representing `numer` / `denom`.
+  ///
+  /// # Panics
+  /// Panics if `denom` is zero, or the reduced fraction is not representable (e.g. `Int::MIN` /
+  /// `-1`).
+  ///
+  /// # Time complexity
+  /// $O(\log \max(\lvert n \rvert, \lvert d \rvert))$ where $n$ = `numer` and $d$ = `denom`
+  pub fn new(numer: Int, denom: Int) -> Self {
+    assert!(denom != Int::ZERO, "denominator must not be zero");
+    Ratio::reduce(numer, denom).expect("fraction overflowed")
+  }
+
+  /// Returns the numerator.
+  pub fn numer(self) -> Int {
+    self.numer
+  }
+
+  /// Returns the denominator, which is always positive.
+  pub fn denom(self) -> Int {
+    self.denom
+  }
+
+  /// Returns whether `self` is an integer.
+  pub fn is_integer(self) -> bool {
+    self.denom == Int::ONE
+  }
+
+  /// Returns `-self`, or `None` on overflow.
+  pub fn checked_neg(self) -> Option<Self> {
+    Some(Ratio { numer: self.numer.checked_neg()?, denom: self.denom })
+  }
+
+  /// Returns `1 / self`, or `None` if `self` is zero or on overflow.
+  pub fn checked_recip(self) -> Option<Self> {
+    Ratio::reduce(self.denom, self.numer)
+  }
+
+  // Computes `self` ± `rhs` by Henrici's method, which keeps the intermediate values small.
+  fn checked_add_sub(self, rhs: Self, op: fn(Int, Int) -> Option<Int>) -> Option<Self> {
+    let g = gcd(self.denom.unsigned_abs(), rhs.denom.unsigned_abs()).try_into().ok()?;
+    let (b, d) = (self.denom / g, rhs.denom / g);
+    let t = op(self.numer.checked_mul(d)?, rhs.numer.checked_mul(b)?)?;
+    let r = Ratio::reduce(t, g)?;
+    let denom = b.checked_mul(d)?.checked_mul(r.denom)?;
+    Some(Ratio { numer: r.numer, denom })
+  }
+
+  /// Returns `self + rhs`, or `None` on overflow.
+  pub fn checked_add(self, rhs: Self) -> Option<Self> {
+    self.checked_add_sub(rhs, Int::checked_add)
+  }
+
+  /// Returns `self - rhs`, or `None` on overflow.
+  pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+    self.checked_add_sub(rhs, Int::checked_sub)
+  }
+
+  /// Returns `self * rhs`, or `None` on overflow.
+  pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+    let x = Ratio::reduce(self.numer, rhs.denom)?;
+    let y = Ratio::reduce(rhs.numer, self.denom)?;
+    let numer = x.numer.checked_mul(y.numer)?;
+    let denom = x.denom.checked_mul(y.denom)?;
+    Some(Ratio { numer, denom })
+  }
+
+  /// Returns `self / rhs`, or `None` if `rhs` is zero or on overflow.
+  pub fn checked_div(self, rhs: Self) -> Option<Self> {
+    self.checked_mul(rhs.checked_recip()?)
+  }
+}
+
+impl<Int> Default for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  fn default() -> Self {
+    Ratio { numer: Int::ZERO, denom: Int::ONE }
+  }
+}
+
+impl<Int> From<Int> for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  fn from(numer: Int) -> Self {
+    Ratio { numer, denom: Int::ONE }
+  }
+}
+
+impl<Int> Ord for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  /// # Time complexity
+  /// $O(\log \max(b, d))$ where $b$ and $d$ are the denominators
+  fn cmp(&self, other: &Self) -> Ordering {
+    // compares the continued fractions, without multiplications that may overflow
+    let (mut a, mut b, mut c, mut d) = (self.numer, self.denom, other.numer, other.denom);
+    let mut reversed = false;
+    loop {
+      let (q, r) = (a.div_euclid(b), a.rem_euclid(b));
+      let (s, t) = (c.div_euclid(d), c.rem_euclid(d));
+      let ord = match q.cmp(&s) {
+        Ordering::Equal => match (r == Int::ZERO, t == Int::ZERO) {
+          (true, true) => Ordering::Equal,
+          (true, false) => Ordering::Less,
+          (false, true) => Ordering::Greater,
+          (false, false) => {
+            // r/b vs t/d is equivalent to d/t vs b/r, i.e. b/r vs d/t reversed
+            a = b;
+            b = r;
+            c = d;
+            d = t;
+            reversed = !reversed;
+            continue;
+          }
+        },
+        ord => ord,
+      };
+      return if reversed { ord.reverse() } else { ord };
+    }
+  }
+}
+
+impl<Int> PartialOrd for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<Int> Display for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if self.is_integer() {
+      write!(f, "{}", self.numer)
+    } else {
+      write!(f, "{}/{}", self.numer, self.denom)
+    }
+  }
+}
+
+impl<Int> FromStr for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  type Err = ParseRatioError;
+
+  /// Parses a fraction `a/b`, an integer `a` or a decimal `a.b`, optionally with a sign.
+  fn from_str(s: &str) -> Result<Self, ParseRatioError> {
+    fn is_digits(s: &str) -> bool {
+      !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+    }
+    let parse = |s: &str| s.parse::<Int>().map_err(|_| ParseRatioErrorKind::Invalid);
+
+    if let Some(i) = s.find('/') {
+      let (numer, denom) = (parse(&s[..i])?, parse(&s[i + 1..])?);
+      if denom == Int::ZERO {
+        return Err(ParseRatioErrorKind::ZeroDenominator.into());
+      }
+      return Ratio::reduce(numer, denom).ok_or_else(|| ParseRatioErrorKind::Overflow.into());
+    }
+    let i = match s.find('.') {
+      Some(i) => i,
+      None => return Ok(parse(s)?.into()),
+    };
+    let (int, frac) = (&s[..i], s[i + 1..].trim_end_matches('0'));
+    let digits = int.trim_start_matches(&['+', '-'][..]);
+    if int.len() - digits.len() > 1 || !is_digits(digits) || !is_digits(&s[i + 1..]) {
+      return Err(ParseRatioErrorKind::Invalid.into());
+    }
+    // all the digits are valid, so the only possible error is overflow
+    let overflow = |_| ParseRatioErrorKind::Overflow;
+    let numer = [int, frac].concat().parse().map_err(overflow)?;
+    let ten = Int::ONE + Int::ONE + Int::ONE + Int::ONE + Int::ONE;
+    let ten = ten + ten;
+    let denom = ten.checked_pow(frac.len() as u32).ok_or(ParseRatioErrorKind::Overflow)?;
+    Ratio::reduce(numer, denom).ok_or_else(|| ParseRatioErrorKind::Overflow.into())
+  }
+}
+
+/// An error which can be returned when parsing a `Ratio`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ParseRatioError {
+  kind: ParseRatioErrorKind,
+}
+
+impl Display for ParseRatioError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.write_str(match self.kind {
+      ParseRatioErrorKind::Invalid => "invalid rational number",
+      ParseRatioErrorKind::ZeroDenominator => "zero denominator",
+      ParseRatioErrorKind::Overflow => "number too large or too precise",
+    })
+  }
+}
+
+impl Error for ParseRatioError {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum ParseRatioErrorKind {
+  Invalid,
+  ZeroDenominator,
+  Overflow,
+}
+
+impl From<ParseRatioErrorKind> for ParseRatioError {
+  fn from(kind: ParseRatioErrorKind) -> ParseRatioError {
+    ParseRatioError { kind }
+  }
+}
+
+macro_rules! impl_binary_op {
+  ($Op:ident $op:ident $OpAssign:ident $op_assign:ident $checked:ident $msg:expr) => {
+    impl<Int> $Op for Ratio<Int>
+    where
+      Int: PrimSint,
+    {
+      type Output = Self;
+
+      /// # Panics
+      /// Panics on overflow.
+      fn $op(self, rhs: Self) -> Self {
+        self.$checked(rhs).expect($msg)
+      }
+    }
+
+    impl<Int> $OpAssign for Ratio<Int>
+    where
+      Int: PrimSint,
+    {
+      /// # Panics
+      /// Panics on overflow.
+      fn $op_assign(&mut self, rhs: Self) {
+        *self = $Op::$op(*self, rhs);
+      }
+    }
+  };
+}
+
+impl_binary_op!(Add add AddAssign add_assign checked_add "addition overflowed");
+impl_binary_op!(Sub sub SubAssign sub_assign checked_sub "subtraction overflowed");
+impl_binary_op!(Mul mul MulAssign mul_assign checked_mul "multiplication overflowed");
+
+impl<Int> Div for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  type Output = Self;
+
+  /// # Panics
+  /// Panics if `rhs` is zero or on overflow.
+  fn div(self, rhs: Self) -> Self {
+    assert!(rhs.numer != Int::ZERO, "division by zero");
+    self.checked_div(rhs).expect("division overflowed")
+  }
+}
+
+impl<Int> DivAssign for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  /// # Panics
+  /// Panics if `rhs` is zero or on overflow.
+  fn div_assign(&mut self, rhs: Self) {
+    *self = *self / rhs;
+  }
+}
+
+impl<Int> Neg for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  type Output = Self;
+
+  /// # Panics
+  /// Panics on overflow.
+  fn neg(self) -> Self {
+    self.checked_neg().expect("negation overflowed")
+  }
+}
+
+/// The operations panic on overflow.
+impl<Int> Semiring for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  fn add(&self, rhs: &Self) -> Self {
+    *self + *rhs
+  }
+  fn mul(&self, rhs: &Self) -> Self {
+    *self * *rhs
+  }
+  fn zero() -> Self {
+    Ratio::default()
+  }
+  fn one() -> Self {
+    Int::ONE.into()
+  }
+}
+
+impl<Int> CommutativeSemiring for Ratio<Int> where Int: PrimSint {}
+
+impl<Int> Ring for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  fn neg(&self) -> Self {
+    -*self
+  }
+}
+
+impl<Int> Field for Ratio<Int>
+where
+  Int: PrimSint,
+{
+  fn recip(&self) -> Option<Self> {
+    if self.numer == Int::ZERO {
+      None
+    } else {
+      Some(self.checked_recip().expect("reciprocal overflowed"))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use quickcheck_macros::quickcheck;
+
+  // the exact value as `numer` / `denom` in `i128`
+  fn wide(x: Ratio<i64>) -> (i128, i128) {
+    (x.numer.into(), x.denom.into())
+  }
+
+  fn wide_ratio(numer: i128, denom: i128) -> Option<Ratio<i64>> {
+    let g = gcd(numer.unsigned_abs(), denom.unsigned_abs()) as i128;
+    let (numer, denom) = (numer / g * denom.signum(), denom.abs() / g);
+    Some(Ratio { numer: numer.try_into().ok()?, denom: denom.try_into().ok()? })
+  }
+
+  fn ratio(numer: i64, denom: i64) -> Ratio<i64> {
+    Ratio::reduce(numer, denom).unwrap_or_default()
+  }
+
+  #[quickcheck]
+  fn new_prop(numer: i64, denom: i64) {
+    assert_eq!(
+      Ratio::reduce(numer, denom),
+      wide_ratio(numer.into(), denom.into()).filter(|_| denom != 0)
+    );
+  }
+
+  #[quickcheck]
+  fn arith_prop(a: i64, b: i64, c: i64, d: i64) {
+    // also test with small values, where the operations hardly overflow
+    for &(a, b, c, d) in &[(a, b, c, d), (a % 1000, b % 1000, c % 1000, d % 1000)] {
+      let (x, y) = (ratio(a, b), ratio(c, d));
+      let ((a, b), (c, d)) = (wide(x), wide(y));
+      assert_eq!(x.checked_add(y), wide_ratio(a * d + c * b, b * d));
+      assert_eq!(x.checked_sub(y), wide_ratio(a * d - c * b, b * d));
+      assert_eq!(x.checked_mul(y), wide_ratio(a.checked_mul(c).unwrap(), b * d));
+      if c != 0 {
+        assert_eq!(x.checked_div(y), wide_ratio(a * d, b.checked_mul(c).unwrap()));
+      } else {
+        assert_eq!(x.checked_div(y), None);
+      }
+    }
+  }
+
+  #[quickcheck]
+  fn cmp_prop(a: i64, b: i64, c: i64, d: i64) {
+    let (x, y) = (ratio(a, b), ratio(c, d));
+    let ((a, b), (c, d)) = (wide(x), wide(y));
+    assert_eq!(x.cmp(&y), (a * d).cmp(&(c * b)));
+    assert_eq!(x.cmp(&x), Ordering::Equal);
+  }
+
+  #[quickcheck]
+  fn parse_prop(a: i64, b: i64) {
+    let x = ratio(a, b);
+    assert_eq!(x.to_string().parse(), Ok(x));
+    let expected = match Ratio::reduce(a, b) {
+      Some(x) => Ok(x),
+      None if b == 0 => Err(ParseRatioErrorKind::ZeroDenominator.into()),
+      None => Err(ParseRatioErrorKind::Overflow.into()),
+    };
+    assert_eq!(format!("{}/{}", a, b).parse(), expected);
+  }
+
+  #[test]
+  fn parse_decimal() {
+    let parse = |s: &str| s.parse::<Ratio<i64>>();
+    assert_eq!(parse("-1.25"), Ok(Ratio::new(-5, 4)));
+    assert_eq!(parse("+0.500"), Ok(Ratio::new(1, 2)));
+    assert_eq!(parse("-0.5"), Ok(Ratio::new(-1, 2)));
+    assert_eq!(parse("0.10000000000000000000000"), Ok(Ratio::new(1, 10)));
+    assert_eq!(parse("-9223372036854775808"), Ok(Ratio::from(i64::min_value())));
+    assert_eq!(parse("-922337203685477580.8"), Ok(Ratio::new(i64::min_value(), 10)));
+    for s in &["", ".5", "3.", "1.-5", "--1.5", "1.5.0", "a/2", "1/", "1.5/2"] {
+      assert_eq!(parse(s), Err(ParseRatioErrorKind::Invalid.into()), "{:?}", s);
+    }
+    assert_eq!(parse("1/0"), Err(ParseRatioErrorKind::ZeroDenominator.into()));
+    assert_eq!(parse("0.00000000000000000001"), Err(ParseRatioErrorKind::Overflow.into()));
+    assert_eq!(parse("-9223372036854775808/-1"), Err(ParseRatioErrorKind::Overflow.into()));
+  }
+
+  #[test]
+  fn field() {
+    let x = Ratio::new(3i32, 4);
+    let y = Field::div(&x, &Ratio::new(1, 2));
+    assert_eq!(Semiring::add(&y, &Ring::neg(&Ratio::from(-1))), Ratio::new(5, 2));
+    assert_eq!(Ratio::<i32>::zero().recip(), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "addition overflowed")]
+  fn add_overflow() {
+    let _ = Ratio::from(i64::max_value()) + Ratio::from(1);
+  }
+}